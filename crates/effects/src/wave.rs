@@ -22,6 +22,7 @@ pub(super) fn plugin(app: &mut App) {
 /// // Parsed usage
 /// world.spawn(pretty!("`my text`[wave(1, 1)]"));
 /// world.spawn(PrettyTextParser::bundle("`my text`[wave(1, 1)]")?);
+/// world.spawn(pretty!("`my text`[wave(max_height=1)]"));
 ///
 /// // Literal usage
 /// world.spawn((
@@ -0,0 +1,146 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::punctuated::Punctuated;
+
+use crate::ATTR_IDENT;
+
+/// The fields that can be supplied by arguments, i.e. not marked with
+/// `#[pretty_text(skip)]` or `#[pretty_text(atlas)]`.
+pub fn arg_fields(fields: &Punctuated<syn::Field, syn::token::Comma>) -> Vec<&syn::Field> {
+    fields
+        .iter()
+        .filter(|field| {
+            !field.attrs.iter().any(|attr| {
+                attr.path().is_ident(ATTR_IDENT)
+                    && attr
+                        .parse_args::<syn::Ident>()
+                        .is_ok_and(|arg| arg == "skip" || arg == "atlas")
+            })
+        })
+        .collect()
+}
+
+/// Constructs a `component` from `args` and `named_args`.
+///
+/// Expects `args` and `named_args` to be in scope. `original_count` is the number of
+/// fields in the struct, including skipped fields.
+pub fn construct_from_args(
+    ident: &syn::Ident,
+    fields: &[&syn::Field],
+    original_count: usize,
+) -> TokenStream2 {
+    let field_count = fields.len();
+    if field_count == 0 {
+        return quote! {
+            if !args.is_empty() || !named_args.is_empty() {
+                return Err(
+                    bevy::prelude::BevyError::from(format!(
+                        "expected no arguments for {}, got {}",
+                        std::any::type_name::<#ident>(),
+                        args.len() + named_args.len()
+                    ))
+                );
+            }
+            let component = #ident::default();
+        };
+    }
+
+    let named_args = assign_named_args(ident, fields);
+
+    let mut arms = Vec::new();
+    arms.push(quote! {
+        0 => Ok(#ident::default()),
+    });
+
+    for i in 1..=field_count {
+        let current_fields = fields.iter().take(i);
+        let field_assignments = current_fields.enumerate().map(|(i, field)| {
+            let field_name = field.ident.as_ref().unwrap();
+            quote! {
+                #field_name: args[#i]
+                    .parse()
+                    .map_err(|e| bevy::prelude::BevyError::from(format!(
+                        "failed to parse argument {} for field `{}` in `{}`: {}",
+                        #i, stringify!(#field_name), std::any::type_name::<#ident>(), e
+                    )))?,
+            }
+        });
+
+        let base = (original_count != field_count || i != field_count)
+            .then_some(quote! { ..Default::default() })
+            .unwrap_or_default();
+
+        arms.push(quote! {
+            #i => Ok(#ident {
+                #(#field_assignments)*
+                #base
+            }),
+        });
+    }
+
+    arms.push(quote! {
+        _ => Err(bevy::prelude::BevyError::from(format!(
+            "expected at most {} arguments for {}, got {}",
+            #field_count, std::any::type_name::<#ident>(), args.len()
+        ))),
+    });
+
+    quote! {
+        let mut component: #ident = match args.len() {
+            #(#arms)*
+        }?;
+        #named_args
+    }
+}
+
+/// Assigns `named_args` to the fields of a mutable `component`.
+///
+/// Expects `args`, `named_args`, and `component` to be in scope.
+pub fn assign_named_args(ident: &syn::Ident, fields: &[&syn::Field]) -> TokenStream2 {
+    let field_names = fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>();
+    let expected = field_names
+        .iter()
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let arms = fields.iter().enumerate().map(|(i, field)| {
+        let field_ident = field.ident.as_ref().unwrap();
+        let field_name = &field_names[i];
+        quote! {
+            #field_name => {
+                if #i < args.len() {
+                    return Err(bevy::prelude::BevyError::from(format!(
+                        "field `{}` in `{}` is already supplied by argument {}",
+                        #field_name, std::any::type_name::<#ident>(), #i
+                    )));
+                }
+
+                component.#field_ident = arg
+                    .value
+                    .parse()
+                    .map_err(|e| bevy::prelude::BevyError::from(format!(
+                        "failed to parse named argument for field `{}` in `{}`: {}",
+                        #field_name, std::any::type_name::<#ident>(), e
+                    )))?;
+            }
+        }
+    });
+
+    quote! {
+        for arg in named_args.iter() {
+            match arg.name.as_ref() {
+                #(#arms)*
+                name => {
+                    return Err(bevy::prelude::BevyError::from(format!(
+                        "unknown field `{}` in `{}`, expected one of: {}",
+                        name, std::any::type_name::<#ident>(), #expected
+                    )));
+                }
+            }
+        }
+    }
+}
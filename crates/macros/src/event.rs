@@ -0,0 +1,36 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+pub fn derive_dynamic_event_inner(input: TokenStream) -> syn::Result<TokenStream2> {
+    let input: syn::DeriveInput = syn::parse(input)?;
+    let ident = &input.ident;
+    let fields = bevy_macro_utils::get_struct_fields(&input.data)?;
+    let pretty_text_path = quote! { bevy_pretty_text };
+
+    let arg_fields = crate::args::arg_fields(fields);
+    let construct = crate::args::construct_from_args(ident, &arg_fields, fields.len());
+
+    Ok(quote! {
+        impl #pretty_text_path::type_writer::dynamic_events::DynamicEvent for #ident {
+            fn trigger_from_args(
+                &self,
+                args: &[std::borrow::Cow<'static, str>],
+                entity: &mut bevy::prelude::EntityCommands,
+            ) -> bevy::prelude::Result<()> {
+                self.trigger_from_named_args(args, &[], entity)
+            }
+
+            fn trigger_from_named_args(
+                &self,
+                args: &[std::borrow::Cow<'static, str>],
+                named_args: &[#pretty_text_path::dynamic_effects::NamedArg],
+                entity: &mut bevy::prelude::EntityCommands,
+            ) -> bevy::prelude::Result<()> {
+                #construct
+                entity.trigger(component);
+                Ok(())
+            }
+        }
+    })
+}
@@ -2,7 +2,9 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 
+mod args;
 mod effect;
+mod event;
 mod material;
 mod pretty;
 
@@ -22,6 +24,13 @@ pub fn derive_text_effect(input: TokenStream) -> TokenStream {
         .into()
 }
 
+#[proc_macro_derive(DynamicEvent, attributes(pretty_text))]
+pub fn derive_dynamic_event(input: TokenStream) -> TokenStream {
+    event::derive_dynamic_event_inner(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 #[proc_macro]
 pub fn pretty(input: TokenStream) -> TokenStream {
     pretty::parse_pretty_text(input)
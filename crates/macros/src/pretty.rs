@@ -53,14 +53,37 @@ fn tokenize_span(
     handler: &mut impl Iterator<Item = TokenStream2>,
 ) -> syn::Result<TokenStream2> {
     Ok(match span {
-        TextSpanBundle::Span { span, mods } => match span {
+        TextSpanBundle::Span { span, mods, source } => match span {
             Span::Text(text) => {
                 let text = text.as_ref();
                 let mods = &mods.0;
                 quote! {
                     bevy_pretty_text::parser::TextSpanBundle::Span {
                         span: bevy_pretty_text::parser::Span::Text(std::borrow::Cow::Borrowed(#text)),
-                        mods: bevy_pretty_text::parser::Modifiers(vec![#(#mods,)*])
+                        mods: bevy_pretty_text::parser::Modifiers(vec![#(#mods,)*]),
+                        source: #source,
+                    }
+                }
+            }
+            Span::Variable(name) => {
+                let name = name.as_ref();
+                let mods = &mods.0;
+                quote! {
+                    bevy_pretty_text::parser::TextSpanBundle::Span {
+                        span: bevy_pretty_text::parser::Span::Variable(std::borrow::Cow::Borrowed(#name)),
+                        mods: bevy_pretty_text::parser::Modifiers(vec![#(#mods,)*]),
+                        source: #source,
+                    }
+                }
+            }
+            Span::Image(name) => {
+                let name = name.as_ref();
+                let mods = &mods.0;
+                quote! {
+                    bevy_pretty_text::parser::TextSpanBundle::Span {
+                        span: bevy_pretty_text::parser::Span::Image(std::borrow::Cow::Borrowed(#name)),
+                        mods: bevy_pretty_text::parser::Modifiers(vec![#(#mods,)*]),
+                        source: #source,
                     }
                 }
             }
@@ -74,7 +97,8 @@ fn tokenize_span(
                 quote! {
                     bevy_pretty_text::parser::TextSpanBundle::Span {
                         span: bevy_pretty_text::parser::Span::Bundles(vec![#(#bundles,)*]),
-                        mods: bevy_pretty_text::parser::Modifiers(vec![#(#mods,)*])
+                        mods: bevy_pretty_text::parser::Modifiers(vec![#(#mods,)*]),
+                        source: #source,
                     }
                 }
             }
@@ -82,8 +106,11 @@ fn tokenize_span(
         TextSpanBundle::Effect(effect) => {
             quote! { bevy_pretty_text::parser::TextSpanBundle::Effect(#effect) }
         }
-        TextSpanBundle::Event(tag) => {
-            quote! { bevy_pretty_text::parser::TextSpanBundle::Event(#tag) }
+        TextSpanBundle::NamedCallback(callback) => {
+            quote! { bevy_pretty_text::parser::TextSpanBundle::NamedCallback(#callback) }
+        }
+        TextSpanBundle::Event(tag, args) => {
+            quote! { bevy_pretty_text::parser::TextSpanBundle::Event(#tag, #args) }
         }
         TextSpanBundle::Callback(_) => {
             let closure = handler.next().ok_or_else(|| {
@@ -101,5 +128,47 @@ fn tokenize_span(
 
             quote! { bevy_pretty_text::parser::TextSpanBundle::Callback(#handler) }
         }
+        TextSpanBundle::Conditional {
+            variable,
+            then,
+            otherwise,
+        } => {
+            let variable = variable.as_ref();
+            let then = tokenize_branch(then, handler)?;
+            let otherwise = tokenize_branch(otherwise, handler)?;
+
+            quote! {
+                bevy_pretty_text::parser::TextSpanBundle::Conditional {
+                    variable: std::borrow::Cow::Borrowed(#variable),
+                    then: #then,
+                    otherwise: #otherwise,
+                }
+            }
+        }
+        TextSpanBundle::Plural { variable, branches } => {
+            let variable = variable.as_ref();
+            let branches = branches
+                .iter()
+                .map(|branch| tokenize_branch(branch, handler))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            quote! {
+                bevy_pretty_text::parser::TextSpanBundle::Plural {
+                    variable: std::borrow::Cow::Borrowed(#variable),
+                    branches: vec![#(#branches,)*],
+                }
+            }
+        }
     })
 }
+
+fn tokenize_branch(
+    branch: &[TextSpanBundle],
+    handler: &mut impl Iterator<Item = TokenStream2>,
+) -> syn::Result<TokenStream2> {
+    let bundles = branch
+        .iter()
+        .map(|span| tokenize_span(span, handler))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(quote! { vec![#(#bundles,)*] })
+}
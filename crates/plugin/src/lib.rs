@@ -62,20 +62,31 @@
 //! ## Creating Pretty Text
 //! - [Compile-time parsing with `pretty`](crate::pretty)
 //! - [Run-time parsing with `PrettyTextParser`](pretty_text::parser::PrettyTextParser)
+//! - [Loading text from `.pretty` files](pretty_text::asset)
+//! - [Localizing text](pretty_text::localization)
 //!
 //! ## TypeWriter
 //! - [The `TypeWriter` type](pretty_text::type_writer::TypeWriter)
 //! - [Special `TypeWriter` effects](pretty_text::type_writer::hierarchy)
 //! - [Controlling text visibility](pretty_text::type_writer::Reveal)
+//! - [Timing, progress and seeking](pretty_text::type_writer::timeline)
+//! - [Events with arguments](pretty_text::type_writer::dynamic_events)
+//! - [Pausing after punctuation](pretty_text::type_writer::pacing)
+//! - [Typing like a person](pretty_text::type_writer::human)
 //!
 //! ## Parsing
 //! - [Syntax](pretty_text::parser)
 //! - [ECS Structure](pretty_text::parser#ecs-structure)
+//! - [Validating modifiers](pretty_text::validation)
+//! - [Interpolating variables](pretty_text::variables)
+//! - [Inline images](pretty_text::inline_image)
 //!
 //! ## Effects
 //! - [The `pretty_text_effects` crate](pretty_text_effects)
 //! - [ECS effects](pretty_text::dynamic_effects)
 //! - [Shader effects](pretty_text::material)
+//! - [Ruby annotations](pretty_text::ruby)
+//! - [Clickable links](pretty_text::link)
 //!
 //! ## Style
 //! - [The built-in styles](pretty_text::style)
@@ -86,10 +97,12 @@
 //! | Flag              | Description                                   | Default feature |
 //! | ----------------- | --------------------------------------------- | --------------- |
 //! | `default_effects` | Enable the [built-in text effects].           | Yes             |
+//! | `picking`         | Enable [clickable links] with `bevy_picking`. | No              |
 //! | `serialize`       | Enable serialization for [`PrettyTextSpans`]. | No              |
 //!
 //! [`PrettyTextSpans`]: pretty_text::parser::PrettyTextSpans
 //! [built-in text effects]: pretty_text_effects
+//! [clickable links]: pretty_text::link
 
 use bevy::prelude::*;
 
@@ -97,12 +110,20 @@ use bevy::prelude::*;
 pub extern crate pretty_text_effects as effects;
 
 pub use pretty_text::access;
+pub use pretty_text::asset;
 pub use pretty_text::dynamic_effects;
 pub use pretty_text::glyph;
+pub use pretty_text::inline_image;
+#[cfg(feature = "picking")]
+pub use pretty_text::link;
+pub use pretty_text::localization;
 pub use pretty_text::material;
 pub use pretty_text::parser;
+pub use pretty_text::ruby;
 pub use pretty_text::style;
 pub use pretty_text::type_writer;
+pub use pretty_text::validation;
+pub use pretty_text::variables;
 
 /// Statically parses pretty text.
 ///
@@ -137,6 +158,14 @@ pub use pretty_text_macros::pretty;
 /// ```
 pub use pretty_text_macros::DynamicEffect;
 
+/// Derive macro for implementing
+/// [`DynamicEvent`](pretty_text::type_writer::dynamic_events::DynamicEvent).
+///
+/// ```no_run
+#[doc = include_str!("../docs/event.txt")]
+/// ```
+pub use pretty_text_macros::DynamicEvent;
+
 /// Derive macro for implementing
 /// [`TextMaterial2d`](pretty_text::material::TextMaterial2d) and
 /// [`DynamicTextMaterial`](pretty_text::material::DynamicTextMaterial).
@@ -149,15 +178,24 @@ pub use pretty_text_macros::TextMaterial2d;
 pub mod prelude {
     pub use super::PrettyTextPlugin;
     pub use pretty_text::PrettyText;
+    pub use pretty_text::asset::{PrettyTextAsset, PrettyTextFile};
     pub use pretty_text::dynamic_effects::DynamicEffect;
+    pub use pretty_text::inline_image::{InlineImage, PrettyTextImages};
+    #[cfg(feature = "picking")]
+    pub use pretty_text::link::{Link, SpanClicked, SpanHovered, SpanUnhovered};
+    pub use pretty_text::localization::{Locale, LocalizedPrettyText, PrettyTextLocales};
     pub use pretty_text::material::{DynamicTextMaterial, TextMaterial2d};
     pub use pretty_text::parser::PrettyTextParser;
+    pub use pretty_text::ruby::Ruby;
     pub use pretty_text::style::PrettyStyle;
     pub use pretty_text::type_writer::{
-        GlyphRevealed, TypeWriter, TypeWriterFinished, TypeWriterMode, WordRevealed,
-        hierarchy::TypeWriterEvent,
+        GlyphErased, GlyphRevealed, LineRevealed, SentenceRevealed, TypeWriter, TypeWriterFinished,
+        TypeWriterMode, TypeWriterSignal, TypeWriterWaiting, WordRevealed, WordSegmentation,
+        dynamic_events::DynamicEvent, hierarchy::TypeWriterEvent, human::HumanTyping,
+        pacing::TypeWriterPacing, timeline::TypeWriterTimeline,
     };
-    pub use pretty_text_macros::{DynamicEffect, TextMaterial2d, pretty};
+    pub use pretty_text::variables::{LiveVariables, PluralRule, PrettyTextVariables};
+    pub use pretty_text_macros::{DynamicEffect, DynamicEvent, TextMaterial2d, pretty};
 }
 
 #[derive(Debug)]
@@ -0,0 +1,427 @@
+//! Loads [`PrettyTextSpans`] from `.pretty` files.
+//!
+//! A `.pretty` file holds one or many named entries. An entry starts with an
+//! `@key` line and its text continues until the next entry.
+//!
+//! ```text
+//! @greeting
+//! Hello, `traveler`[wave]!
+//!
+//! @farewell
+//! Safe travels.[1]
+//! See you `soon`[!green].
+//! ```
+//!
+//! Blank lines around the text of an entry are ignored, and an entry without
+//! text has no spans. A line of text that starts with `@` is escaped with `@@`.
+//!
+//! Text entities reference an entry with a [`PrettyTextAsset`].
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::asset::*;
+//! #
+//! fn spawn_dialogue(mut commands: Commands, server: Res<AssetServer>) {
+//!     commands.spawn(PrettyTextAsset::new(
+//!         server.load("dialogue.pretty"),
+//!         "greeting",
+//!     ));
+//! }
+//! ```
+//!
+//! The text hierarchy is rebuilt whenever the file is modified, so enabling
+//! Bevy's `file_watcher` feature will hot reload edited text.
+//!
+//! Every entry is parsed when the file is loaded. A syntax error fails the
+//! load with a [`PrettyTextFileError`], which the [`AssetServer`] reports.
+//! When a reload fails, the previously loaded text is kept.
+
+use std::borrow::Cow;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+
+use crate::parser::{PrettyTextParseError, PrettyTextParser, PrettyTextSpans};
+
+/// Loads [`PrettyTextFile`]s and rebuilds [`PrettyTextAsset`] hierarchies.
+#[derive(Debug)]
+pub struct PrettyTextAssetPlugin;
+
+impl Plugin for PrettyTextAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<PrettyTextFile>()
+            .init_asset_loader::<PrettyTextFileLoader>()
+            .add_systems(PostUpdate, rebuild_text.before(bevy::text::Update2dText));
+
+        app.register_type::<PrettyTextAsset>();
+    }
+}
+
+/// A collection of named [`PrettyTextSpans`] loaded from a `.pretty` file.
+///
+/// See [`asset`](crate::asset).
+#[derive(Debug, Default, Clone, Asset, TypePath)]
+pub struct PrettyTextFile {
+    entries: HashMap<String, PrettyTextSpans>,
+}
+
+impl PrettyTextFile {
+    /// Parse the entries of a `.pretty` file.
+    ///
+    /// ```
+    /// # use pretty_text::asset::*;
+    /// # use pretty_text::parser::*;
+    /// let file = PrettyTextFile::parse("@greeting\nHello, `traveler`[wave]!").unwrap();
+    ///
+    /// assert_eq!(
+    ///     file.get("greeting").unwrap().to_markup().unwrap(),
+    ///     "Hello, `traveler`[wave]!",
+    /// );
+    /// ```
+    pub fn parse(file: &str) -> Result<Self, PrettyTextFileError> {
+        let mut entries = HashMap::default();
+        let mut entry: Option<(&str, usize, Vec<&str>)> = None;
+
+        for (i, line) in file.lines().enumerate() {
+            let line_number = i + 1;
+            if let Some(key) = line.strip_prefix('@')
+                && !key.starts_with('@')
+            {
+                let key = key.trim();
+                if key.is_empty() || key.contains(char::is_whitespace) {
+                    return Err(PrettyTextFileError::InvalidKey { line: line_number });
+                }
+
+                if let Some(entry) = entry.replace((key, line_number, Vec::new())) {
+                    insert_entry(&mut entries, entry)?;
+                }
+                continue;
+            }
+
+            match entry.as_mut() {
+                Some((.., lines)) => lines.push(line.strip_prefix('@').unwrap_or(line)),
+                None if line.trim().is_empty() => {}
+                None => return Err(PrettyTextFileError::MissingKey { line: line_number }),
+            }
+        }
+
+        if let Some(entry) = entry {
+            insert_entry(&mut entries, entry)?;
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Returns the spans of the entry `key`.
+    pub fn get(&self, key: &str) -> Option<&PrettyTextSpans> {
+        self.entries.get(key)
+    }
+
+    /// Iterate over the keys of every entry.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+}
+
+fn insert_entry(
+    entries: &mut HashMap<String, PrettyTextSpans>,
+    (key, line, lines): (&str, usize, Vec<&str>),
+) -> Result<(), PrettyTextFileError> {
+    if entries.contains_key(key) {
+        return Err(PrettyTextFileError::DuplicateKey {
+            key: key.into(),
+            line,
+        });
+    }
+
+    let start = lines
+        .iter()
+        .position(|line| !line.trim().is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(start, |end| end + 1);
+
+    // The parser rejects empty text.
+    if start == end {
+        entries.insert(key.into(), PrettyTextSpans(Vec::new()));
+        return Ok(());
+    }
+
+    let spans = PrettyTextParser::spans(&lines[start..end].join("\n")).map_err(|error| {
+        PrettyTextFileError::Parse {
+            key: key.into(),
+            line: line + start + 1,
+            error: Box::new(error),
+        }
+    })?;
+    entries.insert(key.into(), spans);
+
+    Ok(())
+}
+
+/// Loads `.pretty` files into [`PrettyTextFile`]s.
+#[derive(Debug, Default)]
+pub struct PrettyTextFileLoader;
+
+impl AssetLoader for PrettyTextFileLoader {
+    type Asset = PrettyTextFile;
+    type Settings = ();
+    type Error = PrettyTextFileError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        PrettyTextFile::parse(std::str::from_utf8(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["pretty"]
+    }
+}
+
+/// An error produced when loading a [`PrettyTextFile`].
+#[derive(Debug)]
+pub enum PrettyTextFileError {
+    /// The file could not be read.
+    Io(std::io::Error),
+
+    /// The file is not valid UTF-8.
+    Utf8(std::str::Utf8Error),
+
+    /// Text appears before the first `@key` line.
+    MissingKey {
+        /// Line of the text, 1-indexed.
+        line: usize,
+    },
+
+    /// An `@key` line is empty or contains whitespace.
+    InvalidKey {
+        /// Line of the key, 1-indexed.
+        line: usize,
+    },
+
+    /// Multiple entries share the same key.
+    DuplicateKey {
+        /// The duplicated key.
+        key: String,
+        /// Line of the second entry, 1-indexed.
+        line: usize,
+    },
+
+    /// The text of an entry failed to parse.
+    Parse {
+        /// Key of the entry.
+        key: String,
+        /// Line where the text of the entry starts, 1-indexed.
+        line: usize,
+        /// The parse error, relative to the text of the entry.
+        error: Box<PrettyTextParseError>,
+    },
+}
+
+impl std::fmt::Display for PrettyTextFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read file: {err}"),
+            Self::Utf8(err) => write!(f, "file is not valid UTF-8: {err}"),
+            Self::MissingKey { line } => {
+                write!(f, "expected an `@key` line before the text on line {line}")
+            }
+            Self::InvalidKey { line } => write!(f, "invalid key on line {line}"),
+            Self::DuplicateKey { key, line } => {
+                write!(f, "duplicate key `{key}` on line {line}")
+            }
+            Self::Parse { key, line, error } => {
+                write!(f, "in entry `{key}` starting on line {line}:\n{error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PrettyTextFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Utf8(err) => Some(err),
+            Self::Parse { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for PrettyTextFileError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<std::str::Utf8Error> for PrettyTextFileError {
+    fn from(err: std::str::Utf8Error) -> Self {
+        Self::Utf8(err)
+    }
+}
+
+/// Builds a text hierarchy from an entry of a [`PrettyTextFile`].
+///
+/// The hierarchy is built once the file is loaded, and rebuilt when the file
+/// is modified or this component changes. Rebuilding despawns every child of
+/// the entity.
+///
+/// See [`asset`](crate::asset).
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct PrettyTextAsset(pub Handle<PrettyTextFile>, pub Cow<'static, str>);
+
+impl PrettyTextAsset {
+    /// Reference the entry `key` of `file`.
+    pub fn new(file: Handle<PrettyTextFile>, key: impl Into<Cow<'static, str>>) -> Self {
+        Self(file, key.into())
+    }
+}
+
+fn rebuild_text(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<PrettyTextFile>>,
+    files: Res<Assets<PrettyTextFile>>,
+    texts: Query<(Entity, Ref<PrettyTextAsset>)>,
+) {
+    let modified = modified_files(&mut events);
+
+    for (entity, text) in texts.iter() {
+        if !text.is_changed() && !modified.contains(&text.0.id()) {
+            continue;
+        }
+
+        let Some(file) = files.get(&text.0) else {
+            continue;
+        };
+
+        let Some(spans) = file.get(&text.1) else {
+            error!(
+                "entry `{}` not found in `{}`",
+                text.1,
+                display_path(&text.0)
+            );
+            continue;
+        };
+
+        rebuild(&mut commands, entity, spans);
+    }
+}
+
+// Files that finished loading or were modified.
+pub(crate) fn modified_files(
+    events: &mut EventReader<AssetEvent<PrettyTextFile>>,
+) -> HashSet<AssetId<PrettyTextFile>> {
+    events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect()
+}
+
+// Replaces the text hierarchy of `entity` with `spans`.
+pub(crate) fn rebuild(commands: &mut Commands, entity: Entity, spans: &PrettyTextSpans) {
+    commands
+        .entity(entity)
+        .despawn_related::<Children>()
+        .insert(spans.clone());
+}
+
+fn display_path(handle: &Handle<PrettyTextFile>) -> String {
+    handle
+        .path()
+        .map(ToString::to_string)
+        .unwrap_or_else(|| format!("{:?}", handle.id()))
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::parser::{ParseErrorKind, PrettyTextSpans};
+    use crate::test::{prepare_app, text};
+
+    use super::{PrettyTextAsset, PrettyTextFile, PrettyTextFileError};
+
+    #[test]
+    fn parse_file() {
+        let file = PrettyTextFile::parse(
+            "\n@greeting\n\nHello, `traveler`[wave]!\n\n@farewell\r\nSafe travels.\n@@ home\n",
+        )
+        .unwrap();
+
+        let mut keys = file.keys().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, vec!["farewell", "greeting"]);
+        assert_eq!(
+            file.get("greeting").unwrap().to_markup().unwrap(),
+            "Hello, `traveler`[wave]!"
+        );
+        assert_eq!(
+            file.get("farewell").unwrap().to_markup().unwrap(),
+            "Safe travels.\n@ home"
+        );
+
+        let file = PrettyTextFile::parse("@empty\n\n@key\ntext\n@last").unwrap();
+        assert_eq!(file.get("empty"), Some(&PrettyTextSpans(Vec::new())));
+        assert_eq!(file.get("last"), Some(&PrettyTextSpans(Vec::new())));
+
+        assert!(matches!(
+            PrettyTextFile::parse("text\n@key\ntext"),
+            Err(PrettyTextFileError::MissingKey { line: 1 })
+        ));
+        assert!(matches!(
+            PrettyTextFile::parse("@my key\ntext"),
+            Err(PrettyTextFileError::InvalidKey { line: 1 })
+        ));
+        assert!(matches!(
+            PrettyTextFile::parse("@key\na\n@key\nb"),
+            Err(PrettyTextFileError::DuplicateKey { line: 3, .. })
+        ));
+
+        let Err(PrettyTextFileError::Parse { key, line, error }) =
+            PrettyTextFile::parse("@key\na\n@broken\n\n`unclosed")
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((key.as_str(), line), ("broken", 5));
+        assert_eq!(error.kind(), ParseErrorKind::UnclosedSpan);
+    }
+
+    #[test]
+    fn rebuild_modified_file() {
+        let mut app = prepare_app();
+        let file = PrettyTextFile::parse("@key\n`first`[!red]").unwrap();
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<PrettyTextFile>>()
+            .add(file);
+
+        let entity = app
+            .world_mut()
+            .spawn(PrettyTextAsset::new(handle.clone(), "key"))
+            .id();
+        app.update();
+        assert_eq!(text(&app, entity), "first");
+
+        let file = PrettyTextFile::parse("@key\nsecond, `third`[!red]").unwrap();
+        app.world_mut()
+            .resource_mut::<Assets<PrettyTextFile>>()
+            .insert(&handle, file);
+        // Asset events are sent after the text is laid out.
+        app.update();
+        app.update();
+        assert_eq!(text(&app, entity), "second, third");
+    }
+}
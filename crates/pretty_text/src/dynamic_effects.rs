@@ -35,6 +35,19 @@
 //!     arg1: 10,
 //!     ..Default::default()
 //! };
+//!
+//! // Named arguments example
+//!
+//! world.spawn(
+//!     pretty!("`my shaky text span`[shake(arg2=0.5)]"),
+//! //                    arg1 is defaulted! -^
+//! );
+//!
+//! // This is just syntax sugar for:
+//! Shake {
+//!     arg2: 0.5,
+//!     ..Default::default()
+//! };
 //! ```
 //!
 //! # Defining Custom Effects
@@ -95,20 +108,78 @@ pub trait DynamicEffect: Send + Sync + 'static {
         args: &[Cow<'static, str>],
         entity: &mut EntityCommands,
     ) -> Result<()>;
+
+    /// Construct a dynamic effect from positional `args` followed by `named_args`
+    /// and insert into `entity`.
+    ///
+    /// The default implementation does not support named arguments and
+    /// forwards `args` to [`DynamicEffect::insert_from_args`].
+    ///
+    /// Returns a [`BevyError`] if the effect can not constructed from the arguments.
+    fn insert_from_named_args(
+        &self,
+        args: &[Cow<'static, str>],
+        named_args: &[NamedArg],
+        entity: &mut EntityCommands,
+    ) -> Result<()> {
+        if let Some(arg) = named_args.first() {
+            return Err(format!(
+                "effect does not support named arguments, got `{}`",
+                arg.name
+            )
+            .into());
+        }
+
+        self.insert_from_args(args, entity)
+    }
+
+    /// The names of the fields that can be constructed from `args`, in order.
+    ///
+    /// Returns `None` if the fields are unknown, in which case
+    /// [validation](crate::validation) will not check the number of arguments.
+    fn fields(&self) -> Option<&'static [&'static str]> {
+        None
+    }
 }
 
 /// A dynamic representation of a text effect.
 ///
 /// Used by [`bevy_pretty_text::parser`] to dynamically insert text effects.
-#[derive(Debug, Clone, Reflect)]
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 pub struct PrettyTextEffect {
     /// Tag associated to a [registered dynamic effect](PrettyTextEffectAppExt).
     pub tag: Cow<'static, str>,
 
-    /// Field arguments for a dynamic effect.
+    /// Positional field arguments for a dynamic effect.
     pub args: Vec<Cow<'static, str>>,
+
+    /// Named field arguments for a dynamic effect, following the positional `args`.
+    pub named_args: Vec<NamedArg>,
+}
+
+/// A named field argument, e.g. ``"`my text`[wave(max_height=20)]"``.
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+pub struct NamedArg {
+    /// Name of the field.
+    pub name: Cow<'static, str>,
+
+    /// Value of the field.
+    pub value: Cow<'static, str>,
+}
+
+impl NamedArg {
+    /// Creates a new named argument.
+    #[inline]
+    pub fn new(name: impl Into<Cow<'static, str>>, value: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
 }
 
 /// Dynamic effect registry.
@@ -155,15 +226,78 @@ impl quote::ToTokens for PrettyTextEffect {
         use quote::TokenStreamExt;
         let tag = &self.tag;
         let args = &self.args;
+        let named_args = &self.named_args;
         tokens.append_all(quote::quote! {
             bevy_pretty_text::dynamic_effects::PrettyTextEffect {
                 tag: std::borrow::Cow::Borrowed(#tag),
-                args: vec![#(std::borrow::Cow::Borrowed(#args),)*]
+                args: vec![#(std::borrow::Cow::Borrowed(#args),)*],
+                named_args: vec![#(#named_args,)*],
+            }
+        });
+    }
+}
+
+#[cfg(feature = "proc-macro")]
+impl quote::ToTokens for NamedArg {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        use quote::TokenStreamExt;
+        let name = &self.name;
+        let value = &self.value;
+        tokens.append_all(quote::quote! {
+            bevy_pretty_text::dynamic_effects::NamedArg {
+                name: std::borrow::Cow::Borrowed(#name),
+                value: std::borrow::Cow::Borrowed(#value),
             }
         });
     }
 }
 
+// Pairs the `fields` of the effect `tag` with positional `args` followed by `named_args`.
+//
+// Used by effects that implement `insert_from_named_args` by hand.
+pub(crate) fn field_args<'a>(
+    tag: &str,
+    fields: &'static [&'static str],
+    args: &'a [Cow<'static, str>],
+    named_args: &'a [NamedArg],
+) -> Result<Vec<(&'static str, &'a str)>> {
+    if args.len() > fields.len() {
+        return Err(format!(
+            "expected at most {} arguments for `{tag}`, got {}",
+            fields.len(),
+            args.len()
+        )
+        .into());
+    }
+
+    let mut pairs = fields
+        .iter()
+        .copied()
+        .zip(args.iter().map(AsRef::as_ref))
+        .collect::<Vec<_>>();
+    for arg in named_args.iter() {
+        let Some(field) = fields.iter().find(|field| **field == arg.name) else {
+            let expected = fields
+                .iter()
+                .map(|field| format!("`{field}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(format!(
+                "unknown field `{}` in `{tag}`, expected one of: {expected}",
+                arg.name
+            )
+            .into());
+        };
+
+        if pairs.iter().any(|(name, _)| name == field) {
+            return Err(format!("field `{field}` in `{tag}` is already supplied").into());
+        }
+        pairs.push((field, arg.value.as_ref()));
+    }
+
+    Ok(pairs)
+}
+
 pub(crate) fn text_effect(
     trigger: Trigger<OnAdd, Modifiers>,
     mut commands: Commands,
@@ -193,9 +327,14 @@ pub(crate) fn text_effect(
                 .insert(ErasedPrettyTextMaterial {
                     tag: effect.tag.clone(),
                     args: effect.args.clone(),
+                    named_args: effect.named_args.clone(),
                 });
         } else if let Some(handler) = effects_registry.0.get(effect.tag.as_ref()) {
-            handler.insert_from_args(&effect.args, &mut commands.entity(trigger.target()))?;
+            handler.insert_from_named_args(
+                &effect.args,
+                &effect.named_args,
+                &mut commands.entity(trigger.target()),
+            )?;
         } else {
             error!("effect `{}` is not registered", effect.tag);
         }
@@ -213,7 +352,7 @@ mod test {
     use crate::parser::{Modifier, Modifiers};
     use crate::test::{prepare_app, run, run_tests};
 
-    use super::{DynamicEffect, PrettyTextEffectAppExt};
+    use super::{DynamicEffect, NamedArg, PrettyTextEffectAppExt, field_args};
 
     #[derive(Default, Component)]
     struct Effect;
@@ -246,6 +385,7 @@ mod test {
                     .insert(Modifiers(vec![Modifier::Effect(PrettyTextEffect {
                         tag: "effect".into(),
                         args: vec!["1".into(), "2".into()],
+                        named_args: Vec::new(),
                     })]));
 
                 app.world_mut().run_schedule(PostUpdate);
@@ -274,4 +414,41 @@ mod test {
             },
         )
     }
+
+    #[test]
+    fn named_field_args() {
+        const FIELDS: &[&str] = &["text", "scale"];
+        let args = ["a".into()];
+
+        assert_eq!(
+            field_args("ruby", FIELDS, &args, &[NamedArg::new("scale", "2")]).unwrap(),
+            vec![("text", "a"), ("scale", "2")]
+        );
+
+        for (args, named_args, err) in [
+            (
+                vec!["a".into(), "1".into(), "b".into()],
+                Vec::new(),
+                "expected at most 2 arguments for `ruby`, got 3",
+            ),
+            (
+                args.to_vec(),
+                vec![NamedArg::new("size", "2")],
+                "unknown field `size` in `ruby`, expected one of: `text`, `scale`",
+            ),
+            (
+                args.to_vec(),
+                vec![NamedArg::new("text", "b")],
+                "field `text` in `ruby` is already supplied",
+            ),
+            (
+                Vec::new(),
+                vec![NamedArg::new("scale", "1"), NamedArg::new("scale", "2")],
+                "field `scale` in `ruby` is already supplied",
+            ),
+        ] {
+            let result = field_args("ruby", FIELDS, &args, &named_args);
+            assert!(result.is_err_and(|result| result.to_string().contains(err)));
+        }
+    }
 }
@@ -15,6 +15,8 @@ use bevy::{
 };
 
 use crate::PrettyText;
+use crate::inline_image::{ImageGlyph, ImageSpanFont, PrettyTextImages, TextImage};
+use crate::ruby::{RubyAnnotation, RubyAnnotationOf};
 
 const DEFAULT_FONT_SIZE: f32 = 20f32;
 
@@ -240,9 +242,18 @@ fn glyphify_text2d(
             &Anchor,
             Option<&RenderLayers>,
         ),
-        (Changed<TextLayoutInfo>, With<PrettyText>, With<Text2d>),
+        (
+            Changed<TextLayoutInfo>,
+            With<PrettyText>,
+            With<Text2d>,
+            Without<RubyAnnotationOf>,
+        ),
     >,
     fonts: Query<&TextFont>,
+    images: Res<PrettyTextImages>,
+    image_spans: Query<(&TextImage, &ImageSpanFont)>,
+    ruby_spans: Query<&RubyAnnotation>,
+    annotations: Query<(&TextLayoutInfo, &TextFont), With<RubyAnnotationOf>>,
     windows: Query<&Window, With<PrimaryWindow>>,
 ) -> Result {
     let scale_factor = windows
@@ -258,6 +269,18 @@ fn glyphify_text2d(
         let text_entities = computed.entities();
         let layers = layers.cloned().unwrap_or_default();
 
+        let size = Vec2::new(
+            text_bounds.width.unwrap_or(layout.size.x),
+            text_bounds.height.unwrap_or(layout.size.y),
+        );
+        let bottom_left = -(anchor.as_vec() + 0.5) * size + (size.y - layout.size.y) * Vec2::Y;
+        // TODO: z ordering?
+        let glyph_transform = |glyph: &PositionedGlyph| {
+            *gt * GlobalTransform::from_translation(bottom_left.extend(0.))
+                * scaling
+                * GlobalTransform::from_translation(glyph.position.extend(0f32))
+        };
+
         for glyph in layout.glyphs.iter() {
             if !processed_spans.contains(&glyph.span_index) {
                 processed_spans.push(glyph.span_index);
@@ -267,17 +290,34 @@ fn glyphify_text2d(
                     .insert((PrettyText, SpanAtlasImage(glyph.atlas_info.texture.clone())));
             }
 
-            let size = Vec2::new(
-                text_bounds.width.unwrap_or(layout.size.x),
-                text_bounds.height.unwrap_or(layout.size.y),
-            );
-            let bottom_left = -(anchor.as_vec() + 0.5) * size + (size.y - layout.size.y) * Vec2::Y;
-            // TODO: z ordering?
-            let transform = *gt
-                * GlobalTransform::from_translation(bottom_left.extend(0.))
-                * scaling
-                * GlobalTransform::from_translation(glyph.position.extend(0f32));
+            // Inline images replace their placeholder glyph with a sprite.
+            let span_entity = text_entities[glyph.span_index].entity;
+            if let Ok((image, span_font)) = image_spans.get(span_entity)
+                && let Some(image) = images.get(&image.0)
+            {
+                let size = image.size * span_font.font_size * scale_factor;
+                let glyph = crate::inline_image::image_glyph(glyph, size);
+                let transform = glyph_transform(&glyph);
+                commands.spawn((
+                    Visibility::Visible,
+                    GlyphOf(entity),
+                    Glyph(glyph),
+                    GlyphSpanEntity(span_entity),
+                    GlyphScale(gt.scale().xy() * span_font.font_size / DEFAULT_FONT_SIZE),
+                    ImageGlyph,
+                    Sprite {
+                        image: image.image.clone(),
+                        custom_size: Some(size),
+                        ..Default::default()
+                    },
+                    transform.compute_transform(),
+                    transform,
+                    layers.clone(),
+                ));
+                continue;
+            }
 
+            let transform = glyph_transform(glyph);
             let font = fonts
                 .get(text_entities[glyph.span_index].entity)
                 .map_err(|_| "invalid text hierarchy: `TextSpan` has no `TextFont`")?;
@@ -293,6 +333,43 @@ fn glyphify_text2d(
                 layers.clone(),
             ));
         }
+
+        // Ruby annotations are laid out separately and placed above their span.
+        for (span_index, span) in text_entities.iter().enumerate() {
+            let Ok(annotation) = ruby_spans.get(span.entity) else {
+                continue;
+            };
+            let Ok((annotation_layout, font)) = annotations.get(annotation.get()) else {
+                continue;
+            };
+
+            let base = layout
+                .glyphs
+                .iter()
+                .filter(|glyph| glyph.span_index == span_index)
+                .cloned()
+                .collect::<Vec<_>>();
+            let glyphs = crate::ruby::layout_annotation(&base, annotation_layout);
+            if let Some(glyph) = glyphs.first() {
+                commands
+                    .entity(annotation.get())
+                    .insert(SpanAtlasImage(glyph.atlas_info.texture.clone()));
+            }
+
+            for glyph in glyphs {
+                let transform = glyph_transform(&glyph);
+                commands.spawn((
+                    Visibility::Visible,
+                    GlyphOf(entity),
+                    Glyph(glyph),
+                    GlyphSpanEntity(annotation.get()),
+                    GlyphScale(gt.scale().xy() * font.font_size / DEFAULT_FONT_SIZE),
+                    transform.compute_transform(),
+                    transform,
+                    layers.clone(),
+                ));
+            }
+        }
     }
 
     Ok(())
@@ -304,7 +381,7 @@ fn insert_glyph_mesh(
     mut meshes: ResMut<Assets<Mesh>>,
     mut glyph_cache: ResMut<GlyphCache>,
     atlases: Res<Assets<TextureAtlasLayout>>,
-    glyphs: Query<(Entity, &Glyph, &GlyphSpanEntity), Changed<Glyph>>,
+    glyphs: Query<(Entity, &Glyph, &GlyphSpanEntity), (Changed<Glyph>, Without<ImageGlyph>)>,
     text_color: Query<&TextColor>,
 ) -> Result {
     use bevy::{
@@ -527,7 +604,7 @@ fn glyph_scale(
         (Entity, &GlobalTransform, &TextFont),
         Or<(Changed<GlobalTransform>, Changed<TextFont>)>,
     >,
-    mut glyphs: Query<(&mut GlyphScale, &GlyphSpanEntity)>,
+    mut glyphs: Query<(&mut GlyphScale, &GlyphSpanEntity), Without<ImageGlyph>>,
 ) {
     for (entity, gt, font) in spans.iter() {
         for (mut scale, _) in glyphs.iter_mut().filter(|(_, span)| span.0 == entity) {
@@ -0,0 +1,341 @@
+//! Places images in the flow of text with the `{img:name}` syntax.
+//!
+//! Images are registered by name in the [`PrettyTextImages`] resource.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::inline_image::*;
+#![doc = include_str!("../docs/pretty.txt")]
+//! #
+//! fn setup(
+//!     mut commands: Commands,
+//!     server: Res<AssetServer>,
+//!     mut images: ResMut<PrettyTextImages>,
+//! ) {
+//!     images.insert("button_a", InlineImage::new(server.load("button_a.png")));
+//!     images.insert(
+//!         "sword",
+//!         InlineImage::new(server.load("sword.png")).with_size(Vec2::new(2.0, 1.0)),
+//!     );
+//!
+//!     commands.spawn(pretty!("Press {img:button_a} to `jump`[wave]"));
+//!     commands.spawn(pretty!("You found a `{img:sword}`[shake]!"));
+//! }
+//! ```
+//!
+//! An image reserves space in the layout equal to its advance, and sits on the
+//! baseline of its line. Its size is relative to the font size of its span.
+//!
+//! An image that is not found is displayed as its placeholder, e.g. `{img:sword}`.
+//!
+//! # ECS Structure
+//!
+//! An image is a [`TextSpan`] with a [`TextImage`]. The span holds a single
+//! placeholder character, whose font size is adjusted to advance by the width
+//! of the image.
+//!
+//! The span produces a single [`Glyph`](crate::glyph::Glyph) entity with an
+//! [`ImageGlyph`] and a [`Sprite`] instead of a glyph mesh. Like any other
+//! glyph, it is moved by [ECS effects](crate::dynamic_effects) on the span and
+//! revealed as one unit by a [`TypeWriter`](crate::type_writer::TypeWriter).
+//! [Shader effects](crate::material) are not applied to images.
+
+use std::borrow::Cow;
+
+use bevy::ecs::world::DeferredWorld;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::text::{CosmicFontSystem, LineHeight, PositionedGlyph, TextPipeline};
+
+/// Sizes the [`TextImage`] spans from the [`PrettyTextImages`].
+#[derive(Debug)]
+pub struct InlineImagePlugin;
+
+impl Plugin for InlineImagePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PrettyTextImages>()
+            .add_systems(
+                PostUpdate,
+                update_image_spans.before(bevy::text::Update2dText),
+            )
+            .add_observer(insert_placeholder);
+
+        app.register_type::<TextImage>()
+            .register_type::<ImageGlyph>();
+    }
+}
+
+// Single byte so that the image is revealed as one unit.
+const PLACEHOLDER: char = '#';
+
+/// Stores the images displayed inline with text.
+///
+/// See [`inline_image`](crate::inline_image).
+#[derive(Debug, Default, Resource)]
+pub struct PrettyTextImages(HashMap<Cow<'static, str>, InlineImage>);
+
+impl PrettyTextImages {
+    /// Register an image with `name`.
+    ///
+    /// Returns the previous image of `name`, if any.
+    pub fn insert(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        image: InlineImage,
+    ) -> Option<InlineImage> {
+        self.0.insert(name.into(), image)
+    }
+
+    /// Remove the image of `name`.
+    pub fn remove(&mut self, name: &str) -> Option<InlineImage> {
+        self.0.remove(name)
+    }
+
+    /// Returns the image of `name`.
+    pub fn get(&self, name: &str) -> Option<&InlineImage> {
+        self.0.get(name)
+    }
+}
+
+/// An image displayed inline with text.
+///
+/// See [`inline_image`](crate::inline_image).
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct InlineImage {
+    /// The image.
+    pub image: Handle<Image>,
+
+    /// Size of the image relative to the font size of its span.
+    ///
+    /// The width is the advance of the image.
+    pub size: Vec2,
+}
+
+impl InlineImage {
+    /// Creates a new inline image that is as tall and wide as the font size.
+    pub fn new(image: Handle<Image>) -> Self {
+        Self {
+            image,
+            size: Vec2::ONE,
+        }
+    }
+
+    /// Set the size of the image relative to the font size of its span.
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = size;
+        self
+    }
+}
+
+/// A [`TextSpan`] that displays an [`InlineImage`].
+///
+/// Produced by the `{img:name}` syntax, see [`inline_image`](crate::inline_image).
+#[derive(Debug, Clone, PartialEq, Eq, Component, Reflect)]
+#[require(TextSpan, ImageSpanFont)]
+pub struct TextImage(pub Cow<'static, str>);
+
+/// Marks a [`Glyph`](crate::glyph::Glyph) that displays an [`InlineImage`]
+/// with a [`Sprite`].
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct ImageGlyph;
+
+// The font size of the span before it was adjusted for the image.
+#[derive(Debug, Component)]
+pub(crate) struct ImageSpanFont {
+    pub(crate) font_size: f32,
+    line_height: LineHeight,
+    adjusted: f32,
+}
+
+impl Default for ImageSpanFont {
+    fn default() -> Self {
+        Self {
+            font_size: 0.,
+            line_height: LineHeight::default(),
+            adjusted: f32::NAN,
+        }
+    }
+}
+
+fn placeholder(name: &str) -> String {
+    format!("{{img:{name}}}")
+}
+
+fn insert_placeholder(trigger: Trigger<OnAdd, TextImage>, mut world: DeferredWorld) {
+    let entity = trigger.target();
+    let name = &world.get::<TextImage>(entity).unwrap().0;
+    let text = match world
+        .get_resource::<PrettyTextImages>()
+        .and_then(|images| images.get(name))
+    {
+        Some(_) => PLACEHOLDER.to_string(),
+        None => {
+            warn!("inline image `{name}` not found");
+            placeholder(name)
+        }
+    };
+    world.get_mut::<TextSpan>(entity).unwrap().0 = text;
+}
+
+fn update_image_spans(
+    images: Res<PrettyTextImages>,
+    pipeline: Res<TextPipeline>,
+    mut font_system: ResMut<CosmicFontSystem>,
+    mut spans: Query<(&TextImage, &mut TextSpan, &mut TextFont, &mut ImageSpanFont)>,
+) {
+    for (image, mut span, mut font, mut span_font) in spans.iter_mut() {
+        // The font was changed by something else, e.g. a style.
+        if font.font_size != span_font.adjusted {
+            span_font.font_size = font.font_size;
+            span_font.line_height = font.line_height;
+            span_font.adjusted = font.font_size;
+        }
+
+        let Some(image) = images.get(&image.0) else {
+            let text = placeholder(&image.0);
+            if span.0 != text {
+                warn!("inline image `{}` not found", image.0);
+                span.0 = text;
+                font.font_size = span_font.font_size;
+                font.line_height = span_font.line_height;
+                span_font.adjusted = span_font.font_size;
+            }
+            continue;
+        };
+
+        if span.0.len() != 1 || !span.0.starts_with(PLACEHOLDER) {
+            span.0 = PLACEHOLDER.to_string();
+        }
+
+        // The font is known once it has been laid out.
+        let Some(advance) = placeholder_advance(&pipeline, &mut font_system, &font.font) else {
+            continue;
+        };
+
+        let font_size = span_font.font_size * image.size.x / advance;
+        if font.font_size != font_size {
+            font.font_size = font_size;
+            // The line height of the span is not affected by the image.
+            font.line_height = LineHeight::Px(match span_font.line_height {
+                LineHeight::Px(px) => px,
+                LineHeight::RelativeToFont(scale) => scale * span_font.font_size,
+            });
+            span_font.adjusted = font_size;
+        }
+    }
+}
+
+// Advance of the placeholder, relative to the font size.
+fn placeholder_advance(
+    pipeline: &TextPipeline,
+    font_system: &mut CosmicFontSystem,
+    font: &Handle<Font>,
+) -> Option<f32> {
+    let font = font_system.get_font(pipeline.get_font_id(font.id())?)?;
+    let face = font.rustybuzz();
+    let advance = face.glyph_hor_advance(face.glyph_index(PLACEHOLDER)?)?;
+    Some(advance as f32 / face.units_per_em() as f32)
+}
+
+/// Positions an image of `size` on the baseline of the placeholder `glyph`.
+pub(crate) fn image_glyph(glyph: &PositionedGlyph, size: Vec2) -> PositionedGlyph {
+    let offset = glyph.atlas_info.location.offset.as_vec2();
+    let pen = glyph.position.x - glyph.size.x / 2. - offset.x;
+    let baseline = glyph.position.y - offset.y + glyph.size.y / 2.;
+
+    PositionedGlyph {
+        position: Vec2::new(pen, baseline) + size / 2.,
+        size,
+        ..glyph.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+    use bevy::time::TimeUpdateStrategy;
+
+    use crate::glyph::{Glyph, GlyphOf, GlyphSpanEntity};
+    use crate::parser::PrettyTextParser;
+    use crate::test::prepare_app;
+    use crate::type_writer::{GlyphRevealed, TypeWriter};
+
+    use super::{ImageGlyph, InlineImage, PrettyTextImages, TextImage};
+
+    #[derive(Default, Resource)]
+    struct Revealed(Vec<GlyphRevealed>);
+
+    fn positions(app: &mut App, root: Entity) -> Vec<f32> {
+        let mut glyphs = app.world_mut().query::<(&Glyph, &GlyphOf)>();
+        let mut glyphs = glyphs
+            .iter(app.world())
+            .filter(|(_, glyph_of)| glyph_of.root() == root)
+            .map(|(glyph, _)| (glyph.0.byte_index, glyph.0.position.x))
+            .collect::<Vec<_>>();
+        glyphs.sort_by_key(|(byte_index, _)| *byte_index);
+        glyphs.into_iter().map(|(_, x)| x).collect()
+    }
+
+    #[test]
+    fn image_glyph() {
+        let mut app = prepare_app();
+        app.init_resource::<Revealed>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+        app.world_mut()
+            .resource_mut::<PrettyTextImages>()
+            .insert("star", InlineImage::new(Handle::default()));
+
+        let text = app
+            .world_mut()
+            .spawn(PrettyTextParser::bundle("ab").unwrap())
+            .id();
+        let image = app
+            .world_mut()
+            .spawn((
+                TypeWriter::new(10.),
+                PrettyTextParser::bundle("a{img:star}b{img:missing}").unwrap(),
+            ))
+            .observe(
+                |trigger: Trigger<GlyphRevealed>, mut revealed: ResMut<Revealed>| {
+                    revealed.0.push(trigger.event().clone());
+                },
+            )
+            .id();
+        for _ in 0..4 {
+            app.update();
+        }
+
+        let mut spans = app.world_mut().query::<(&TextImage, &TextSpan)>();
+        let spans = spans
+            .iter(app.world())
+            .map(|(image, span)| (image.0.as_ref(), span.0.as_str()))
+            .collect::<Vec<_>>();
+        assert!(spans.contains(&("star", "#")));
+        assert!(spans.contains(&("missing", "{img:missing}")));
+
+        let mut images = app
+            .world_mut()
+            .query_filtered::<(Entity, &Glyph, &GlyphSpanEntity), (With<ImageGlyph>, With<Sprite>)>(
+            );
+        let (image_glyph, glyph, span) = images.single(app.world()).unwrap();
+        assert_eq!(glyph.0.size, Vec2::splat(20.));
+        assert!(app.world().get::<TextImage>(span.0).is_some());
+
+        // The image advances by its width.
+        let text = positions(&mut app, text);
+        let image_text = positions(&mut app, image);
+        let advance = image_text[2] - image_text[0] - (text[1] - text[0]);
+        assert!((advance - 20.).abs() < 1., "advance: {advance}");
+
+        // The image is revealed as a single glyph.
+        let revealed = &app.world().resource::<Revealed>().0;
+        assert_eq!(revealed[1].glyph, Some(image_glyph));
+        assert_eq!(revealed[1].image.as_deref(), Some("star"));
+        assert_eq!(revealed[2].text, "b");
+        assert_eq!(revealed[2].image, None);
+    }
+}
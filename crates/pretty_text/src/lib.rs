@@ -11,12 +11,20 @@ use bevy::prelude::*;
 extern crate self as bevy_pretty_text;
 
 pub mod access;
+pub mod asset;
 pub mod dynamic_effects;
 pub mod glyph;
+pub mod inline_image;
+#[cfg(feature = "picking")]
+pub mod link;
+pub mod localization;
 pub mod material;
 pub mod parser;
+pub mod ruby;
 pub mod style;
 pub mod type_writer;
+pub mod validation;
+pub mod variables;
 
 /// Top level text component.
 ///
@@ -54,14 +62,23 @@ impl Plugin for PrettyTextCorePlugin {
         }
 
         app.add_plugins((
+            asset::PrettyTextAssetPlugin,
             glyph::GlyphMeshPlugin,
+            inline_image::InlineImagePlugin,
+            localization::LocalizationPlugin,
+            ruby::RubyPlugin,
             type_writer::TypeWriterPlugin,
             style::StylePlugin,
+            variables::VariablesPlugin,
         ))
         .init_resource::<dynamic_effects::DynEffectRegistry>()
         .add_observer(dynamic_effects::text_effect)
         .add_observer(parser::pretty_text_spans)
-        .register_type::<PrettyText>();
+        .register_type::<PrettyText>()
+        .register_type::<parser::MarkupSource>();
+
+        #[cfg(feature = "picking")]
+        app.add_plugins(link::LinkPlugin);
 
         material::plugin(app);
     }
@@ -93,6 +110,35 @@ mod test {
         app
     }
 
+    // Like `prepare_app`, with an empty `memory://` asset source. Loads from the source
+    // complete within a few updates, without waiting on the file system.
+    pub fn prepare_memory_app() -> App {
+        use bevy::asset::io::AssetSourceBuilder;
+        use bevy::asset::io::memory::MemoryAssetReader;
+
+        let mut app = App::new();
+        app.register_asset_source(
+            "memory",
+            AssetSourceBuilder::default().with_reader(|| Box::new(MemoryAssetReader::default())),
+        );
+
+        app.add_plugins((
+            MinimalPlugins,
+            // Required for the text systems
+            AssetPlugin::default(),
+            bevy::render::texture::ImagePlugin::default(),
+            bevy::text::TextPlugin,
+            bevy::image::TextureAtlasPlugin,
+            //
+            super::PrettyTextCorePlugin,
+        ));
+
+        app.finish();
+        app.cleanup();
+
+        app
+    }
+
     pub fn prepare_app_with(f: impl FnOnce(&mut App)) -> App {
         let mut app = App::new();
 
@@ -120,6 +166,17 @@ mod test {
         world.run_system_once(system).unwrap()
     }
 
+    // The text of the spans directly below `entity`.
+    pub fn text(app: &App, entity: Entity) -> String {
+        app.world()
+            .get::<Children>(entity)
+            .into_iter()
+            .flatten()
+            .filter_map(|child| app.world().get::<TextSpan>(*child))
+            .map(|span| span.0.as_str())
+            .collect()
+    }
+
     pub fn run_tests(
         mut app: impl FnMut() -> App,
         mut test: impl FnMut(&mut App, Entity, &'static str),
@@ -0,0 +1,471 @@
+//! Makes spans interactive with [`bevy_picking`](bevy::picking).
+//!
+//! The `link` effect marks a span with a tag. Hovering or clicking any glyph of
+//! the span emits [`SpanHovered`], [`SpanUnhovered`] and [`SpanClicked`].
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::link::*;
+#![doc = include_str!("../docs/pretty.txt")]
+//! #
+//! # let mut world = World::new();
+//! world
+//!     .spawn(pretty!(
+//!         "Beware `the ancient relic`[link(relic, hover_style=red)]."
+//!     ))
+//!     .observe(|trigger: Trigger<SpanClicked>| {
+//!         assert_eq!(trigger.tag, "relic");
+//!     });
+//! ```
+//!
+//! The events are both triggered on the text root and emitted, meaning that
+//! they can be received by an [`Observer`] and an [`EventReader`].
+//!
+//! While a span is hovered, the [`TextColor`] of its `hover_style` is applied
+//! to it.
+//!
+//! # Picking
+//!
+//! [`LinkPlugin`] provides a picking backend for the [`Glyph`]s of link spans.
+//! The bounds of a glyph are derived from the size of its [`PositionedGlyph`],
+//! so the gaps between glyphs are not hovered.
+//!
+//! Glyphs are picked with an orthographic camera. A [`Pickable`] component on
+//! a glyph is respected.
+//!
+//! [`PositionedGlyph`]: bevy::text::PositionedGlyph
+
+use std::borrow::Cow;
+
+use bevy::picking::PickSet;
+use bevy::picking::backend::{HitData, PointerHits};
+use bevy::picking::hover::HoverMap;
+use bevy::picking::pointer::{PointerId, PointerLocation};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::PrettyText;
+use crate::dynamic_effects::{DynamicEffect, NamedArg, PrettyTextEffectAppExt, field_args};
+use crate::glyph::{Glyph, GlyphOf, GlyphSpanEntity, Glyphs};
+use crate::style::PrettyStyleRegistry;
+
+/// Registers the `link` effect and picks the [`Glyph`]s of link spans.
+#[derive(Debug)]
+pub struct LinkPlugin;
+
+impl Plugin for LinkPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_pretty_effect::<Link>(Link::TAG)
+            .add_event::<PointerHits>()
+            .add_event::<SpanHovered>()
+            .add_event::<SpanUnhovered>()
+            .add_event::<SpanClicked>()
+            .add_systems(
+                PreUpdate,
+                (
+                    glyph_picking.in_set(PickSet::Backend),
+                    hover_links.in_set(PickSet::PostHover),
+                ),
+            )
+            .add_observer(click_link);
+
+        app.register_type::<Link>()
+            .register_type::<LinkHovered>()
+            .register_type::<SpanHovered>()
+            .register_type::<SpanUnhovered>()
+            .register_type::<SpanClicked>();
+    }
+}
+
+/// Makes a span interactive, e.g. ``"`the ancient relic`[link(relic)]"``.
+///
+/// See [`link`](crate::link).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Component, Reflect)]
+#[require(PrettyText)]
+pub struct Link {
+    /// Identifies the link in [`SpanHovered`], [`SpanUnhovered`] and [`SpanClicked`].
+    pub tag: String,
+
+    /// The [style](crate::style) applied while the span is hovered.
+    pub hover_style: Option<String>,
+}
+
+impl Link {
+    /// The tag of the `link` effect.
+    pub const TAG: &'static str = "link";
+}
+
+impl DynamicEffect for Link {
+    fn insert_from_args(
+        &self,
+        args: &[Cow<'static, str>],
+        entity: &mut EntityCommands,
+    ) -> Result<()> {
+        self.insert_from_named_args(args, &[], entity)
+    }
+
+    fn insert_from_named_args(
+        &self,
+        args: &[Cow<'static, str>],
+        named_args: &[NamedArg],
+        entity: &mut EntityCommands,
+    ) -> Result<()> {
+        let mut link = Self::default();
+        for (field, value) in field_args(Self::TAG, self.fields().unwrap(), args, named_args)? {
+            match field {
+                "tag" => link.tag = value.to_string(),
+                "hover_style" => link.hover_style = Some(value.to_string()),
+                _ => unreachable!("unknown fields are rejected by `field_args`"),
+            }
+        }
+
+        entity.insert(link);
+        Ok(())
+    }
+
+    fn fields(&self) -> Option<&'static [&'static str]> {
+        Some(&["tag", "hover_style"])
+    }
+}
+
+/// Marks a [`Link`] span that is hovered by a pointer.
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct LinkHovered {
+    root: Entity,
+    // The color of the span before the hover style was applied.
+    restore: Option<TextColor>,
+}
+
+/// An event triggered by a text root when a pointer enters a [`Link`] span.
+///
+/// See [`link`](crate::link).
+#[derive(Debug, Clone, PartialEq, Eq, Event, Reflect)]
+pub struct SpanHovered {
+    /// The tag of the [`Link`].
+    pub tag: String,
+
+    /// The span entity.
+    pub span: Entity,
+}
+
+/// An event triggered by a text root when a pointer leaves a [`Link`] span.
+///
+/// See [`link`](crate::link).
+#[derive(Debug, Clone, PartialEq, Eq, Event, Reflect)]
+pub struct SpanUnhovered {
+    /// The tag of the [`Link`].
+    pub tag: String,
+
+    /// The span entity.
+    pub span: Entity,
+}
+
+/// An event triggered by a text root when a [`Link`] span is clicked.
+///
+/// See [`link`](crate::link).
+#[derive(Debug, Clone, PartialEq, Eq, Event, Reflect)]
+pub struct SpanClicked {
+    /// The tag of the [`Link`].
+    pub tag: String,
+
+    /// The span entity.
+    pub span: Entity,
+}
+
+fn glyph_picking(
+    pointers: Query<(&PointerId, &PointerLocation)>,
+    cameras: Query<(Entity, &Camera, &GlobalTransform, &Projection)>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    glyphs: Query<(
+        Entity,
+        &Glyph,
+        &GlyphSpanEntity,
+        &GlobalTransform,
+        &ViewVisibility,
+        Option<&Pickable>,
+    )>,
+    links: Query<(), With<Link>>,
+    mut output: EventWriter<PointerHits>,
+) {
+    let mut sorted_glyphs = glyphs
+        .iter()
+        .filter(|(_, _, span, transform, vis, _)| {
+            links.contains(span.0) && vis.get() && !transform.affine().is_nan()
+        })
+        .collect::<Vec<_>>();
+    sorted_glyphs.sort_by(|(_, _, _, a, _, _), (_, _, _, b, _, _)| {
+        b.translation().z.total_cmp(&a.translation().z)
+    });
+
+    let primary_window = primary_window.single().ok();
+    for (pointer, location) in pointers
+        .iter()
+        .filter_map(|(pointer, location)| location.location().map(|loc| (pointer, loc)))
+    {
+        let Some((cam_entity, camera, cam_transform, Projection::Orthographic(cam_ortho))) =
+            cameras
+                .iter()
+                .filter(|(_, camera, ..)| camera.is_active)
+                .find(|(_, camera, ..)| {
+                    camera
+                        .target
+                        .normalize(primary_window)
+                        .is_some_and(|target| target == location.target)
+                })
+        else {
+            continue;
+        };
+
+        let viewport_pos = camera
+            .logical_viewport_rect()
+            .map(|v| v.min)
+            .unwrap_or_default();
+        let Ok(ray) = camera.viewport_to_world(cam_transform, location.position - viewport_pos)
+        else {
+            continue;
+        };
+        let ray_end = ray.origin + ray.direction * (cam_ortho.far - cam_ortho.near);
+
+        let mut blocked = false;
+        let picks = sorted_glyphs
+            .iter()
+            .filter_map(|(entity, glyph, _, transform, _, pickable)| {
+                if blocked {
+                    return None;
+                }
+
+                // Intersect the ray with the plane of the glyph.
+                let world_to_glyph = transform.affine().inverse();
+                let start = world_to_glyph.transform_point3(ray.origin);
+                let end = world_to_glyph.transform_point3(ray_end);
+                if start.z == end.z {
+                    return None;
+                }
+                let t = f32::inverse_lerp(start.z, end.z, 0.);
+                if !(0.0..=1.0).contains(&t) {
+                    return None;
+                }
+                let position = start.lerp(end, t).xy();
+
+                if !Rect::from_center_size(Vec2::ZERO, glyph.0.size).contains(position) {
+                    return None;
+                }
+                blocked = pickable.is_none_or(|pickable| pickable.should_block_lower);
+
+                let hit = transform.transform_point(position.extend(0.));
+                let depth =
+                    -cam_ortho.near - cam_transform.affine().inverse().transform_point3(hit).z;
+                Some((
+                    *entity,
+                    HitData::new(cam_entity, depth, Some(hit), Some(*transform.back())),
+                ))
+            })
+            .collect();
+
+        output.write(PointerHits::new(*pointer, picks, camera.order as f32));
+    }
+}
+
+fn hover_links(
+    mut commands: Commands,
+    hover_map: Option<Res<HoverMap>>,
+    mut hovered_writer: EventWriter<SpanHovered>,
+    mut unhovered_writer: EventWriter<SpanUnhovered>,
+    registry: Res<PrettyStyleRegistry>,
+    glyph_spans: Query<(&GlyphOf, &GlyphSpanEntity)>,
+    mut links: Query<(&Link, Option<&LinkHovered>, Option<&mut TextColor>)>,
+    hovered_links: Query<Entity, With<LinkHovered>>,
+    roots: Query<&Glyphs>,
+    mut glyphs: Query<(&GlyphSpanEntity, &mut Glyph)>,
+    styles: Query<&TextColor, Without<Link>>,
+) {
+    // Maps hovered spans to their root.
+    let mut hovered = HashMap::<Entity, Entity>::default();
+    for entity in hover_map
+        .iter()
+        .flat_map(|map| map.values())
+        .flat_map(|hits| hits.keys())
+    {
+        if let Ok((glyph_of, span)) = glyph_spans.get(*entity)
+            && links.contains(span.0)
+        {
+            hovered.insert(span.0, glyph_of.root());
+        }
+    }
+
+    // Rebuilds the glyph meshes of `span` with its new color.
+    let mut mark_changed = |root: Entity, span: Entity| {
+        let Ok(root_glyphs) = roots.get(root) else {
+            return;
+        };
+        let mut iter = glyphs.iter_many_mut(root_glyphs.iter());
+        while let Some((glyph_span, mut glyph)) = iter.fetch_next() {
+            if glyph_span.0 == span {
+                glyph.set_changed();
+            }
+        }
+    };
+
+    for span in hovered_links.iter() {
+        if hovered.contains_key(&span) {
+            continue;
+        }
+
+        let Ok((link, Some(state), color)) = links.get_mut(span) else {
+            continue;
+        };
+        if let (Some(restore), Some(mut color)) = (state.restore, color) {
+            *color = restore;
+            mark_changed(state.root, span);
+        }
+
+        let event = SpanUnhovered {
+            tag: link.tag.clone(),
+            span,
+        };
+        commands.entity(span).remove::<LinkHovered>();
+        commands.entity(state.root).trigger(event.clone());
+        unhovered_writer.write(event);
+    }
+
+    for (span, root) in hovered {
+        let Ok((link, None, color)) = links.get_mut(span) else {
+            continue;
+        };
+
+        let style_color = link
+            .hover_style
+            .as_deref()
+            .and_then(|style| registry.get(style))
+            .and_then(|style| styles.get(style).ok());
+        let restore = match (style_color, color) {
+            (Some(style_color), Some(mut color)) => {
+                let restore = *color;
+                *color = *style_color;
+                mark_changed(root, span);
+                Some(restore)
+            }
+            _ => None,
+        };
+
+        let event = SpanHovered {
+            tag: link.tag.clone(),
+            span,
+        };
+        commands.entity(span).insert(LinkHovered { root, restore });
+        commands.entity(root).trigger(event.clone());
+        hovered_writer.write(event);
+    }
+}
+
+fn click_link(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut writer: EventWriter<SpanClicked>,
+    glyphs: Query<(&GlyphOf, &GlyphSpanEntity)>,
+    links: Query<&Link>,
+) {
+    let Ok((glyph_of, span)) = glyphs.get(trigger.target()) else {
+        return;
+    };
+    let Ok(link) = links.get(span.0) else {
+        return;
+    };
+
+    let event = SpanClicked {
+        tag: link.tag.clone(),
+        span: span.0,
+    };
+    commands.entity(glyph_of.root()).trigger(event.clone());
+    writer.write(event);
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::color::palettes::css::RED;
+    use bevy::picking::backend::HitData;
+    use bevy::picking::hover::HoverMap;
+    use bevy::picking::pointer::PointerId;
+    use bevy::platform::collections::HashMap;
+    use bevy::prelude::*;
+
+    use crate::glyph::{GlyphOf, GlyphSpanEntity};
+    use crate::parser::PrettyTextParser;
+    use crate::test::prepare_app;
+
+    use super::{Link, LinkHovered, SpanHovered, SpanUnhovered};
+
+    #[derive(Default, Resource)]
+    struct Hovers(Vec<(bool, String)>);
+
+    #[test]
+    fn hover_link() {
+        let mut app = prepare_app();
+        app.init_resource::<Hovers>();
+        app.update();
+
+        let root = app
+            .world_mut()
+            .spawn(
+                PrettyTextParser::bundle("Beware `the relic`[link(relic, hover_style=red)]")
+                    .unwrap(),
+            )
+            .observe(
+                |trigger: Trigger<SpanHovered>, mut hovers: ResMut<Hovers>| {
+                    hovers.0.push((true, trigger.tag.clone()));
+                },
+            )
+            .observe(
+                |trigger: Trigger<SpanUnhovered>, mut hovers: ResMut<Hovers>| {
+                    hovers.0.push((false, trigger.tag.clone()));
+                },
+            )
+            .id();
+        app.update();
+        app.update();
+
+        let mut glyphs = app
+            .world_mut()
+            .query::<(Entity, &GlyphOf, &GlyphSpanEntity)>();
+        let (glyph, span) = glyphs
+            .iter(app.world())
+            .filter(|(_, glyph_of, _)| glyph_of.root() == root)
+            .find_map(|(glyph, _, span)| app.world().get::<Link>(span.0).map(|_| (glyph, span.0)))
+            .unwrap();
+        assert_eq!(
+            app.world().get::<Link>(span),
+            Some(&Link {
+                tag: "relic".into(),
+                hover_style: Some("red".into()),
+            })
+        );
+        let color = *app.world().get::<TextColor>(span).unwrap();
+
+        let hit = HitData::new(Entity::PLACEHOLDER, 0., None, None);
+        let mut map = HashMap::default();
+        map.insert(
+            PointerId::Mouse,
+            [(glyph, hit)].into_iter().collect::<HashMap<_, _>>(),
+        );
+        app.world_mut().insert_resource(HoverMap(map));
+        app.update();
+        app.update();
+
+        assert!(app.world().get::<LinkHovered>(span).is_some());
+        assert_eq!(
+            app.world().get::<TextColor>(span),
+            Some(&TextColor(RED.into()))
+        );
+
+        app.world_mut().insert_resource(HoverMap::default());
+        app.update();
+
+        assert!(app.world().get::<LinkHovered>(span).is_none());
+        assert_eq!(app.world().get::<TextColor>(span), Some(&color));
+        assert_eq!(
+            app.world().resource::<Hovers>().0,
+            [(true, "relic".into()), (false, "relic".into())]
+        );
+    }
+}
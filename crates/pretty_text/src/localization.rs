@@ -0,0 +1,289 @@
+//! Localizes text with per-locale tables of markup.
+//!
+//! A table is a [`PrettyTextFile`] keyed by message id. Tables are registered
+//! for each locale in [`PrettyTextLocales`], and the current [`Locale`] selects
+//! which table [`LocalizedPrettyText`] entities are spawned from.
+//!
+//! `locales/en.pretty`:
+//!
+//! ```text
+//! @npc.greeting
+//! Hello, `traveler`[wave]!
+//! ```
+//!
+//! `locales/ja.pretty`:
+//!
+//! ```text
+//! @npc.greeting
+//! `旅人`[wave]さん、こんにちは！
+//! ```
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::localization::*;
+//! #
+//! fn setup(
+//!     mut commands: Commands,
+//!     server: Res<AssetServer>,
+//!     mut locales: ResMut<PrettyTextLocales>,
+//! ) {
+//!     locales.insert("en", server.load("locales/en.pretty"));
+//!     locales.insert("ja", server.load("locales/ja.pretty"));
+//!     locales.set_fallback("en");
+//!
+//!     commands.insert_resource(Locale::new("ja"));
+//!     commands.spawn(LocalizedPrettyText::new("npc.greeting"));
+//! }
+//! ```
+//!
+//! Text is re-spawned when the [`Locale`] changes, or when a table is loaded
+//! or modified.
+//!
+//! If the current locale has no table, its table failed to load, or its table
+//! is missing a message, the message is taken from the fallback locale. Without
+//! a [`Locale`] resource, the fallback locale is used.
+
+use std::borrow::Cow;
+
+use bevy::asset::{AssetLoadFailedEvent, LoadState};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+
+use crate::asset::{self, PrettyTextFile, modified_files};
+use crate::parser::PrettyTextSpans;
+
+/// Spawns [`LocalizedPrettyText`] hierarchies from the [`PrettyTextLocales`].
+#[derive(Debug)]
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PrettyTextLocales>().add_systems(
+            PostUpdate,
+            rebuild_localized_text.before(bevy::text::Update2dText),
+        );
+
+        app.register_type::<Locale>()
+            .register_type::<LocalizedPrettyText>();
+    }
+}
+
+/// The current locale, e.g. `"en"`.
+///
+/// See [`localization`](crate::localization).
+#[derive(Debug, Clone, PartialEq, Eq, Resource, Reflect)]
+pub struct Locale(pub Cow<'static, str>);
+
+impl Locale {
+    /// Creates a new locale.
+    pub fn new(locale: impl Into<Cow<'static, str>>) -> Self {
+        Self(locale.into())
+    }
+}
+
+/// Stores the table of messages for each locale.
+///
+/// See [`localization`](crate::localization).
+#[derive(Debug, Default, Resource)]
+pub struct PrettyTextLocales {
+    tables: HashMap<Cow<'static, str>, Handle<PrettyTextFile>>,
+    fallback: Option<Cow<'static, str>>,
+}
+
+impl PrettyTextLocales {
+    /// Register the table of `locale`.
+    ///
+    /// Returns the previous table of `locale`, if any.
+    pub fn insert(
+        &mut self,
+        locale: impl Into<Cow<'static, str>>,
+        table: Handle<PrettyTextFile>,
+    ) -> Option<Handle<PrettyTextFile>> {
+        self.tables.insert(locale.into(), table)
+    }
+
+    /// Remove the table of `locale`.
+    pub fn remove(&mut self, locale: &str) -> Option<Handle<PrettyTextFile>> {
+        self.tables.remove(locale)
+    }
+
+    /// Returns the table of `locale`.
+    pub fn get(&self, locale: &str) -> Option<&Handle<PrettyTextFile>> {
+        self.tables.get(locale)
+    }
+
+    /// Set the locale used for missing messages.
+    pub fn set_fallback(&mut self, locale: impl Into<Cow<'static, str>>) {
+        self.fallback = Some(locale.into());
+    }
+
+    /// The locale used for missing messages.
+    pub fn fallback(&self) -> Option<&str> {
+        self.fallback.as_deref()
+    }
+
+    fn lookup<'a>(
+        &self,
+        locale: &str,
+        id: &str,
+        files: &'a Assets<PrettyTextFile>,
+        server: &AssetServer,
+    ) -> Lookup<'a> {
+        let Some(table) = self.tables.get(locale) else {
+            return Lookup::Missing;
+        };
+
+        match files.get(table) {
+            Some(file) => file.get(id).map_or(Lookup::Missing, Lookup::Found),
+            None if matches!(server.load_state(table), LoadState::Failed(_)) => Lookup::Missing,
+            None => Lookup::Loading,
+        }
+    }
+}
+
+enum Lookup<'a> {
+    Found(&'a PrettyTextSpans),
+    Loading,
+    Missing,
+}
+
+/// Builds a text hierarchy from the message `id` in the current [`Locale`].
+///
+/// Rebuilding despawns every child of the entity.
+///
+/// See [`localization`](crate::localization).
+#[derive(Debug, Clone, PartialEq, Eq, Component, Reflect)]
+pub struct LocalizedPrettyText(pub Cow<'static, str>);
+
+impl LocalizedPrettyText {
+    /// Localize the message `id`.
+    pub fn new(id: impl Into<Cow<'static, str>>) -> Self {
+        Self(id.into())
+    }
+}
+
+fn rebuild_localized_text(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<PrettyTextFile>>,
+    mut failed: EventReader<AssetLoadFailedEvent<PrettyTextFile>>,
+    files: Res<Assets<PrettyTextFile>>,
+    server: Res<AssetServer>,
+    locale: Option<Res<Locale>>,
+    locales: Res<PrettyTextLocales>,
+    texts: Query<(Entity, Ref<LocalizedPrettyText>)>,
+    mut warned: Local<HashSet<(String, String)>>,
+) {
+    let mut changed = modified_files(&mut events);
+    changed.extend(failed.read().map(|event| event.id));
+    let tables_changed = locales
+        .tables
+        .values()
+        .any(|table| changed.contains(&table.id()));
+    let rebuild_all = tables_changed
+        || locales.is_changed()
+        || locale.as_ref().is_some_and(|locale| locale.is_changed());
+
+    let locale = locale.as_ref().map(|locale| locale.0.as_ref());
+    for (entity, text) in texts.iter() {
+        if !rebuild_all && !text.is_changed() {
+            continue;
+        }
+
+        let id = text.0.as_ref();
+        let spans = match locale.map(|locale| locales.lookup(locale, id, &files, &server)) {
+            Some(Lookup::Found(spans)) => spans,
+            Some(Lookup::Loading) => continue,
+            Some(Lookup::Missing) | None => {
+                let fallback = locales.fallback();
+                match fallback.map(|fallback| locales.lookup(fallback, id, &files, &server)) {
+                    Some(Lookup::Found(spans)) => {
+                        if let (Some(locale), Some(fallback)) = (locale, fallback)
+                            && warned.insert((locale.into(), id.into()))
+                        {
+                            warn!(
+                                "message `{id}` not found for locale `{locale}`, \
+                                using `{fallback}`"
+                            );
+                        }
+                        spans
+                    }
+                    Some(Lookup::Loading) => continue,
+                    Some(Lookup::Missing) | None => {
+                        error!("message `{id}` not found");
+                        continue;
+                    }
+                }
+            }
+        };
+
+        asset::rebuild(&mut commands, entity, spans);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::asset::PrettyTextFile;
+    use crate::test::{prepare_memory_app, text};
+
+    use super::{Locale, LocalizedPrettyText, PrettyTextLocales};
+
+    #[test]
+    fn localized_text() {
+        let mut app = prepare_memory_app();
+        let mut files = app.world_mut().resource_mut::<Assets<PrettyTextFile>>();
+        let en = files.add(
+            PrettyTextFile::parse("@npc.greeting\n`Hello`[wave]!\n@npc.farewell\nGoodbye!")
+                .unwrap(),
+        );
+        let ja = PrettyTextFile::parse("@npc.greeting\n`こんにちは`[wave]！").unwrap();
+        let ja = files.add(ja);
+
+        let mut locales = app.world_mut().resource_mut::<PrettyTextLocales>();
+        locales.insert("en", en);
+        locales.insert("ja", ja);
+        locales.set_fallback("en");
+        app.world_mut().insert_resource(Locale::new("ja"));
+
+        let greeting = app
+            .world_mut()
+            .spawn(LocalizedPrettyText::new("npc.greeting"))
+            .id();
+        let farewell = app
+            .world_mut()
+            .spawn(LocalizedPrettyText::new("npc.farewell"))
+            .id();
+        app.update();
+        assert_eq!(text(&app, greeting), "こんにちは！");
+        assert_eq!(text(&app, farewell), "Goodbye!");
+
+        app.world_mut().insert_resource(Locale::new("en"));
+        app.update();
+        assert_eq!(text(&app, greeting), "Hello!");
+        assert_eq!(text(&app, farewell), "Goodbye!");
+
+        app.world_mut().insert_resource(Locale::new("fr"));
+        app.update();
+        assert_eq!(text(&app, greeting), "Hello!");
+
+        // A table that fails to load falls back once the load fails.
+        let de = app
+            .world()
+            .resource::<AssetServer>()
+            .load::<PrettyTextFile>("memory://locales/missing.pretty");
+        app.world_mut()
+            .resource_mut::<PrettyTextLocales>()
+            .insert("de", de);
+        app.world_mut().insert_resource(Locale::new("ja"));
+        app.update();
+        assert_eq!(text(&app, greeting), "こんにちは！");
+
+        // The empty memory source fails the load without touching the file system.
+        app.world_mut().insert_resource(Locale::new("de"));
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(text(&app, greeting), "Hello!");
+    }
+}
@@ -70,6 +70,7 @@ use bevy::prelude::*;
 use bevy::sprite::{Material2d, Material2dPlugin};
 
 use crate::PrettyText;
+use crate::dynamic_effects::NamedArg;
 use crate::glyph::{GlyphSystems, SpanAtlasImage};
 
 pub(super) fn plugin(app: &mut App) {
@@ -189,6 +190,39 @@ pub trait DynamicTextMaterial: Send + Sync + 'static {
         entity: &mut EntityCommands,
         server: &AssetServer,
     ) -> Result<()>;
+
+    /// Construct a dynamic material from positional `args` followed by `named_args`
+    /// and insert into `entity`.
+    ///
+    /// The default implementation does not support named arguments and
+    /// forwards `args` to [`DynamicTextMaterial::insert_from_args`].
+    ///
+    /// Returns a [`BevyError`] if the material can not constructed from the arguments.
+    fn insert_from_named_args(
+        &self,
+        args: &[Cow<'static, str>],
+        named_args: &[NamedArg],
+        entity: &mut EntityCommands,
+        server: &AssetServer,
+    ) -> Result<()> {
+        if let Some(arg) = named_args.first() {
+            return Err(format!(
+                "material does not support named arguments, got `{}`",
+                arg.name
+            )
+            .into());
+        }
+
+        self.insert_from_args(args, entity, server)
+    }
+
+    /// The names of the fields that can be constructed from `args`, in order.
+    ///
+    /// Returns `None` if the fields are unknown, in which case
+    /// [validation](crate::validation) will not check the number of arguments.
+    fn fields(&self) -> Option<&'static [&'static str]> {
+        None
+    }
 }
 
 /// A dynamic representation of a text material.
@@ -199,8 +233,11 @@ pub struct ErasedPrettyTextMaterial {
     /// Tag associated to a [registered material](PrettyTextMaterialAppExt).
     pub tag: Cow<'static, str>,
 
-    /// Field arguments for a dynamic material.
+    /// Positional field arguments for a dynamic material.
     pub args: Vec<Cow<'static, str>>,
+
+    /// Named field arguments for a dynamic material, following the positional `args`.
+    pub named_args: Vec<NamedArg>,
 }
 
 /// Dynamic material registry.
@@ -268,6 +305,7 @@ mod sealed {
 
     use crate::PrettyText;
     use crate::glyph::{GlyphOf, GlyphSpanEntity, GlyphSystems};
+    use crate::inline_image::ImageGlyph;
 
     use super::{
         DEFAULT_GLYPH_SHADER_HANDLE, DynMaterialRegistry, DynamicTextMaterial,
@@ -323,6 +361,7 @@ mod sealed {
             (Entity, &GlyphSpanEntity),
             (
                 With<GlyphOf>,
+                Without<ImageGlyph>,
                 Without<Material>,
                 Without<PrettyTextMaterial<T>>,
             ),
@@ -362,7 +401,12 @@ mod sealed {
         })?;
 
         let mut commands = commands.entity(trigger.target());
-        handler.insert_from_args(material.args.as_ref(), &mut commands, &server)?;
+        handler.insert_from_named_args(
+            material.args.as_ref(),
+            material.named_args.as_ref(),
+            &mut commands,
+            &server,
+        )?;
         commands.remove::<ErasedPrettyTextMaterial>();
 
         Ok(())
@@ -467,6 +511,7 @@ mod test {
                     .insert(Modifiers(vec![Modifier::Effect(PrettyTextEffect {
                         tag: "material".into(),
                         args: vec!["1".into(), "2".into()],
+                        named_args: Vec::new(),
                     })]));
 
                 let has_spans = app
@@ -14,6 +14,13 @@
 //!
 //! ``"`Effect with supplied arguments`[my_effect(10, 4.3)]"``
 //!
+//! ``"`Effect with named arguments`[my_effect(10, speed=4.3)]"``
+//!
+//! ``"`Effect with quoted arguments`[my_effect("hello, world", sound="door \"creak\"")]"``
+//!
+//! Quoted arguments may contain any character. Within quotes, `\"` and `\\`
+//! escape a quote and backslash, and `\n` and `\t` produce a newline and tab.
+//!
 //! ``"`Multiple effects`[my_effect, another_effect]"``
 //!
 //! ## Styles
@@ -30,15 +37,62 @@
 //! sequencing:
 //! - Pause: `[seconds]`
 //!     - ex: `"Pause[1] between"`
+//! - Wait for input: `[wait]`
+//!     - ex: `"Press a button[wait] to continue"`
+//! - Wait for a [`TypeWriterSignal`](crate::type_writer::TypeWriterSignal):
+//!   `{wait:tag}`, or with a timeout in seconds: `{wait:tag:seconds}`
+//!     - ex: `"The door creaks...{wait:door_open:5} and opens."`
 //! - Set relative speed: `<mult>`
 //!     - ex: `"<2.0>Fast <0.2>Slow"`
 //! - Emit [`TypeWriterEvent`]s: `{my_event}`
 //!     - ex: `"Emit an {my_event}event"`
+//! - Emit [dynamic events](crate::type_writer::dynamic_events) with arguments:
+//!   `{my_event(args)}`
+//!     - ex: `"The ground {shake_camera(0.5, 2)}rumbles"`
+//! - Run [named callbacks](crate::type_writer::hierarchy::TypeWriterNamedCallback):
+//!   `{@name}`
+//!     - ex: `"The vase {@play_fall_sound}falls"`
 //!
 //! And in the special case of the `pretty` macro:
 //! - Trigger [`TypeWriterCallback`]s: `{}`
 //!     - ex: `pretty!("Trigger a {}callback", |mut commands: Commands| { ... })`
 //!
+//! # Variables
+//!
+//! [Variables](crate::variables) are text spans that display a runtime value:
+//! `{$name}`. They are not parsed as markup.
+//!
+//! ``"Hello, `{$player_name}`[wave]!"``
+//!
+//! [Conditions](crate::variables#conditions) select text with a variable:
+//! `{?name|then|otherwise}`, or with the plural form of a number:
+//! `{#name|one|other}`. Within a branch, `|` is escaped with `\|`.
+//!
+//! ``"{?has_key|You `unlock`[shake] it.|It's locked.}"``
+//!
+//! ``"You have {$apples} {#apples|apple|apples}."``
+//!
+//! # Inline Images
+//!
+//! [Inline images](crate::inline_image) are placed in the flow of text:
+//! `{img:name}`.
+//!
+//! ``"Press {img:button_a} to `jump`[wave]"``
+//!
+//! # Tag Syntax
+//!
+//! [`PrettyTextParser::bbcode`] parses an alternative syntax where modifiers
+//! open and close a span with tags: `"[mod1, ...]...[/mod1]"`. The close tag
+//! names the first modifier, or is left empty: `"[/]"`.
+//!
+//! ``"[wave]Simple effect[/wave]"``
+//!
+//! ``"[my_effect(10, speed=4.3), !my_style]Multiple modifiers[/my_effect]"``
+//!
+//! ``"[!my_style]Nested [wave]spans[/] and styles[/!my_style]"``
+//!
+//! Type writer commands and escapes are the same in both syntaxes.
+//!
 //! # Usage
 //!
 //! ```
@@ -69,6 +123,7 @@
 //! The [`PrettyTextParser`] returns a result that indicates whether or not the
 //! syntax is valid. However, the effects and styles are dynamically constructed
 //! at run time, so the parser *will not* fail to parse unregistered modifiers.
+//! To catch unregistered modifiers before spawning, see [`validation`](crate::validation).
 //!
 //! The static parser, similarly, will produce a compiler error if the syntax is
 //! invalid, but fails to warn about unregistered modifiers.
@@ -128,6 +183,20 @@
 //! ));
 //! ```
 //!
+//! Each spawned [`TextSpan`] carries a [`MarkupSource`] with the byte range of
+//! its text in the parsed string. [`PrettyTextSpans::plain_text`] maps offsets
+//! in the displayed text back to the markup.
+//!
+//! ```
+//! # use pretty_text::parser::*;
+//! let spans = PrettyTextParser::spans("Hello, `world`[wave]!").unwrap();
+//! let plain = spans.plain_text();
+//!
+//! assert_eq!(plain.as_str(), "Hello, world!");
+//! //                                 ^^^^^
+//! assert_eq!(plain.markup_range(7..12), Some(8..13));
+//! ```
+//!
 //! Note that the spans from [`PrettyTextSpans`] will always be represented
 //! as [`TextSpan`] entities, and no text will be placed into the root [`Text2d`]
 //! component.
@@ -154,13 +223,20 @@
 //! ```
 
 use std::borrow::Cow;
+use std::ops::Range;
 
 use bevy::prelude::*;
 
 use crate::PrettyText;
-use crate::dynamic_effects::PrettyTextEffect;
+use crate::dynamic_effects::{NamedArg, PrettyTextEffect};
+use crate::inline_image::TextImage;
 use crate::style::SpanStyle;
-use crate::type_writer::hierarchy::{TypeWriterCallback, TypeWriterCommand, TypeWriterEvent};
+use crate::type_writer::hierarchy::{
+    TypeWriterCallback, TypeWriterCommand, TypeWriterEvent, TypeWriterEventArgs,
+    TypeWriterNamedCallback,
+};
+use crate::validation::{ValidationContext, ValidationError, ValidationErrors};
+use crate::variables::TextVariable;
 
 /// Dynamically parses pretty text.
 ///
@@ -192,14 +268,258 @@ pub struct PrettyTextParser;
 
 impl PrettyTextParser {
     /// Parse `pretty_text` into a bundle.
-    pub fn bundle(pretty_text: &str) -> Result<impl Bundle, String> {
+    pub fn bundle(pretty_text: &str) -> Result<impl Bundle, PrettyTextParseError> {
         Self::spans(pretty_text).map(PrettyTextSpans::into_bundle)
     }
 
     /// Parse `pretty_text` into a collection of spans.
-    pub fn spans(pretty_text: &str) -> Result<PrettyTextSpans, String> {
+    pub fn spans(pretty_text: &str) -> Result<PrettyTextSpans, PrettyTextParseError> {
         sealed::parse_bundles(pretty_text).map(PrettyTextSpans)
     }
+
+    /// Parse `pretty_text` written in [tag syntax](crate::parser#tag-syntax)
+    /// into a collection of spans.
+    ///
+    /// ```
+    /// # use pretty_text::parser::*;
+    /// let spans = PrettyTextParser::bbcode("[wave]my [!red]pretty[/!red] text[/wave]").unwrap();
+    /// assert_eq!(spans.to_markup().unwrap(), "`my `pretty`[!red] text`[wave]");
+    /// ```
+    pub fn bbcode(pretty_text: &str) -> Result<PrettyTextSpans, PrettyTextParseError> {
+        sealed::parse_bbcode(pretty_text).map(PrettyTextSpans)
+    }
+
+    /// Parse `pretty_text` into a collection of spans and [validate](crate::validation)
+    /// the modifiers against the registries in `world`.
+    pub fn validate(pretty_text: &str, world: &World) -> Result<PrettyTextSpans, ValidationErrors> {
+        let spans = Self::spans(pretty_text)
+            .map_err(|err| ValidationErrors(vec![ValidationError::Parse(err)]))?;
+        ValidationContext::new(world).validate(&spans)?;
+        Ok(spans)
+    }
+}
+
+/// An error produced by the [`PrettyTextParser`].
+///
+/// Points to the exact byte range in the source string that failed to parse.
+/// The [`Display`](std::fmt::Display) implementation renders a multi-line
+/// diagnostic.
+///
+/// ```
+/// # use pretty_text::parser::*;
+/// let err = PrettyTextParser::spans("`my span`[]").unwrap_err();
+///
+/// assert_eq!(err.kind(), ParseErrorKind::EmptyModifiers);
+/// assert_eq!(err.span(), 10..11);
+/// assert_eq!(err.token(), Some("]"));
+///
+/// // error: empty modifiers
+/// //  --> 1:11
+/// //   |
+/// // 1 | `my span`[]
+/// //   |           ^
+/// //   |
+/// //   = expected: a single modifier, e.g. `shake`
+/// println!("{err}");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrettyTextParseError {
+    input: String,
+    span: Range<usize>,
+    token: Option<String>,
+    kind: ParseErrorKind,
+    expected: Vec<Cow<'static, str>>,
+}
+
+impl PrettyTextParseError {
+    /// The category of error.
+    #[inline]
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// The byte range in [`PrettyTextParseError::input`] that failed to parse.
+    ///
+    /// If the parser unexpectedly reached the end of the input, the range will
+    /// be empty and start at the end of the input.
+    #[inline]
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// The token that failed to parse.
+    ///
+    /// Returns `None` if the parser unexpectedly reached the end of the input.
+    #[inline]
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// Items that the parser expected to find at [`PrettyTextParseError::span`].
+    #[inline]
+    pub fn expected(&self) -> &[Cow<'static, str>] {
+        &self.expected
+    }
+
+    /// The source string that failed to parse.
+    #[inline]
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// The line and column (both 1-indexed) of the start of
+    /// [`PrettyTextParseError::span`].
+    ///
+    /// Columns are counted in `char`s.
+    pub fn line_col(&self) -> (usize, usize) {
+        let before = &self.input[..self.span.start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let col = self.input[line_start..self.span.start].chars().count() + 1;
+
+        (line, col)
+    }
+}
+
+impl std::fmt::Display for PrettyTextParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (line, col) = self.line_col();
+        let line_start = self.input[..self.span.start]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let line_end = self.input[self.span.start..]
+            .find('\n')
+            .map(|i| self.span.start + i)
+            .unwrap_or(self.input.len());
+        let source_line = &self.input[line_start..line_end];
+
+        // Underline at least one column so that errors at the end of the input
+        // are still visible.
+        let underline = self.input[self.span.start..self.span.end.min(line_end)]
+            .chars()
+            .count()
+            .max(1);
+
+        let gutter = line.to_string();
+        let pad = " ".repeat(gutter.len());
+
+        writeln!(f, "error: {}", self.kind)?;
+        writeln!(f, "{pad}--> {line}:{col}")?;
+        writeln!(f, "{pad} |")?;
+        writeln!(f, "{gutter} | {source_line}")?;
+        write!(
+            f,
+            "{pad} | {}{}",
+            " ".repeat(col - 1),
+            "^".repeat(underline)
+        )?;
+
+        if !self.expected.is_empty() {
+            write!(f, "\n{pad} |\n{pad} = expected: ")?;
+            for (i, expected) in self.expected.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " or ")?;
+                }
+                write!(f, "{expected}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for PrettyTextParseError {}
+
+/// The category of a [`PrettyTextParseError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    /// The input is empty.
+    EmptyInput,
+
+    /// A span was opened with a backtick but never closed, e.g. ``"`my span"``.
+    UnclosedSpan,
+
+    /// A span is not followed by modifiers, e.g. ``"`my span`"``.
+    MissingModifiers,
+
+    /// A span is followed by an empty set of modifiers, e.g. ``"`my span`[]"``.
+    EmptyModifiers,
+
+    /// A span's modifiers are not closed, e.g. ``"`my span`[shake"``.
+    UnclosedModifiers,
+
+    /// A modifier is not a single identifier, e.g. ``"`my span`[my shake]"``.
+    InvalidModifier,
+
+    /// An effect's arguments are malformed, e.g. ``"`my span`[shake(1 2)]"``.
+    InvalidEffectArgs,
+
+    /// A type writer command is malformed, e.g. `"<fast>"` or `"{wait:}"`.
+    InvalidCommand,
+
+    /// A type writer event is malformed, e.g. `"{my_event"`.
+    InvalidEvent,
+
+    /// A [variable](crate::variables) is malformed, e.g. `"{$my variable}"`.
+    InvalidVariable,
+
+    /// A [named callback](crate::type_writer::hierarchy::TypeWriterNamedCallback) is
+    /// malformed, e.g. `"{@my callback}"`.
+    InvalidCallback,
+
+    /// A [condition](crate::variables#conditions) is malformed, e.g.
+    /// `"{?has_key|a|b|c}"`.
+    InvalidCondition,
+
+    /// An [inline image](crate::inline_image) is malformed, e.g. `"{img:}"`.
+    InvalidImage,
+
+    /// A backslash does not escape a special token, e.g. `"\\n"`.
+    InvalidEscape,
+
+    /// A closing delimiter has no matching opening delimiter, e.g. `"my text]"`.
+    UnmatchedDelimiter,
+
+    /// A [tag syntax](PrettyTextParser::bbcode) span is never closed, e.g. `"[wave]my text"`.
+    UnclosedTag,
+
+    /// A [tag syntax](PrettyTextParser::bbcode) close tag does not match the open
+    /// tag, e.g. `"[wave]my text[/shake]"`.
+    MismatchedCloseTag,
+
+    /// A [tag syntax](PrettyTextParser::bbcode) close tag has no open tag, e.g.
+    /// `"my text[/wave]"`.
+    UnexpectedCloseTag,
+
+    /// The parser encountered an unexpected token.
+    UnexpectedToken,
+}
+
+impl std::fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::EmptyInput => "empty input",
+            Self::UnclosedSpan => "unclosed span",
+            Self::MissingModifiers => "span is missing modifiers",
+            Self::EmptyModifiers => "empty modifiers",
+            Self::UnclosedModifiers => "unclosed modifiers",
+            Self::InvalidModifier => "invalid modifier",
+            Self::InvalidEffectArgs => "invalid effect arguments",
+            Self::InvalidCommand => "invalid type writer command",
+            Self::InvalidEvent => "invalid type writer event",
+            Self::InvalidVariable => "invalid variable",
+            Self::InvalidCallback => "invalid callback",
+            Self::InvalidCondition => "invalid condition",
+            Self::InvalidImage => "invalid inline image",
+            Self::InvalidEscape => "invalid escape sequence",
+            Self::UnmatchedDelimiter => "unmatched delimiter",
+            Self::UnclosedTag => "unclosed tag",
+            Self::MismatchedCloseTag => "mismatched close tag",
+            Self::UnexpectedCloseTag => "unexpected close tag",
+            Self::UnexpectedToken => "unexpected token",
+        })
+    }
 }
 
 /// Collection of [text spans](TextSpanBundle).
@@ -211,13 +531,39 @@ impl PrettyTextParser {
 ///
 /// You can serialize [`PrettyTextSpans`] with the `serialize` feature. Any
 /// [`TypeWriterCallback`]s will be skipped. You can emulate callback behaviour
-/// with a [`TypeWriterEvent`] and an [`Observer`] or [`EventReader`].
-#[derive(Debug, Clone, Component, Reflect)]
+/// with a [`TypeWriterNamedCallback`], or a [`TypeWriterEvent`] and an [`Observer`]
+/// or [`EventReader`].
+///
+/// [`PrettyTextSpans::to_markup`] writes canonical markup that parses back into
+/// the same spans.
+///
+/// ```
+/// # use pretty_text::parser::*;
+/// let spans = PrettyTextParser::spans("`escaped \\[`[wave(1,max_height = 2), !red]").unwrap();
+/// let markup = spans.to_markup().unwrap();
+///
+/// assert_eq!(markup, "`escaped \\[`[wave(1, max_height=2), !red]");
+/// assert_eq!(PrettyTextParser::spans(&markup).unwrap().to_markup().unwrap(), markup);
+/// ```
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 pub struct PrettyTextSpans(pub Vec<TextSpanBundle>);
 
 impl PrettyTextSpans {
+    /// Serialize the spans back into markup.
+    ///
+    /// Spans with modifiers but without any content, e.g. `"[wave][/wave]"`,
+    /// display nothing and are omitted.
+    ///
+    /// Fails if the name of an effect, style, event, signal, callback, variable
+    /// or image is not a single word without special tokens, since names
+    /// can not be escaped.
+    #[inline]
+    pub fn to_markup(&self) -> Result<String, InvalidMarkupName> {
+        MarkupWriter::write_string(|writer| self.write_markup(writer))
+    }
+
     /// Produce a valid text hierarchy bundle.
     pub fn into_bundle(self) -> impl Bundle {
         (
@@ -226,6 +572,258 @@ impl PrettyTextSpans {
             Children::spawn(sealed::TextSpanSpawner::from_vec(self.0)),
         )
     }
+
+    /// Collect the text of every span with the markup stripped.
+    ///
+    /// [Variables](crate::variables), conditions and [inline images](crate::inline_image)
+    /// are not resolved and are skipped.
+    ///
+    /// The returned [`PlainText`] maps offsets in the plain text back to the
+    /// [`MarkupSource`] of each span.
+    pub fn plain_text(&self) -> PlainText {
+        let mut plain = PlainText::default();
+        for bundle in self.0.iter() {
+            plain.push_bundle(bundle);
+        }
+        plain
+    }
+}
+
+impl PrettyTextSpans {
+    fn write_markup(&self, writer: &mut MarkupWriter) -> std::fmt::Result {
+        self.0
+            .iter()
+            .try_for_each(|bundle| bundle.write_markup(writer, false))
+    }
+}
+
+/// Writes markup, see [`PrettyTextSpans::to_markup`].
+///
+/// A name that can not be written is written as escaped text, so the markup may
+/// not parse back into the same spans.
+impl std::fmt::Display for PrettyTextSpans {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_markup(&mut MarkupWriter::lossy(f))
+    }
+}
+
+/// Text with the markup stripped, see [`PrettyTextSpans::plain_text`].
+///
+/// Offsets are measured in bytes.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PlainText {
+    text: String,
+    // Markup range of each byte in `text`.
+    offsets: Vec<Range<usize>>,
+    end: usize,
+}
+
+impl PlainText {
+    /// The plain text.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Map a byte offset in the plain text to a byte offset in the markup.
+    ///
+    /// The end of the plain text maps to the end of the last span. Returns
+    /// `None` if `offset` is out of bounds.
+    pub fn markup_offset(&self, offset: usize) -> Option<usize> {
+        if offset == self.text.len() {
+            Some(self.end)
+        } else {
+            self.offsets.get(offset).map(|range| range.start)
+        }
+    }
+
+    /// Map a byte range in the plain text to a byte range in the markup.
+    ///
+    /// Escaped characters include their backslash. Returns `None` if `range`
+    /// is out of bounds.
+    pub fn markup_range(&self, range: Range<usize>) -> Option<Range<usize>> {
+        if range.start >= range.end {
+            let offset = self.markup_offset(range.start)?;
+            return Some(offset..offset);
+        }
+
+        let start = self.offsets.get(range.start)?.start;
+        let end = self.offsets.get(range.end - 1)?.end;
+        Some(start..end)
+    }
+
+    fn push_bundle(&mut self, bundle: &TextSpanBundle) {
+        let TextSpanBundle::Span { span, source, .. } = bundle else {
+            return;
+        };
+
+        match span {
+            Span::Text(text) => {
+                let mut offset = source.range.start;
+                for c in text.chars() {
+                    let start = offset;
+                    if source.escapes.binary_search(&offset).is_ok() {
+                        offset += 1;
+                    }
+                    offset += c.len_utf8();
+
+                    self.text.push(c);
+                    self.offsets
+                        .extend(std::iter::repeat_n(start..offset, c.len_utf8()));
+                }
+                self.end = source.range.end;
+            }
+            Span::Variable(_) | Span::Image(_) => {}
+            Span::Bundles(bundles) => {
+                for bundle in bundles.iter() {
+                    self.push_bundle(bundle);
+                }
+            }
+        }
+    }
+}
+
+/// A name that can not be written as markup, see [`PrettyTextSpans::to_markup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMarkupName(pub String);
+
+impl std::fmt::Display for InvalidMarkupName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` can not be written as markup", self.0)
+    }
+}
+
+impl std::error::Error for InvalidMarkupName {}
+
+// Writes markup, recording the first name that can not be written.
+struct MarkupWriter<'a> {
+    out: &'a mut dyn std::fmt::Write,
+    invalid: Option<InvalidMarkupName>,
+    // Writes invalid names as escaped text instead of failing.
+    lossy: bool,
+}
+
+impl<'a> MarkupWriter<'a> {
+    fn new(out: &'a mut dyn std::fmt::Write) -> Self {
+        Self {
+            out,
+            invalid: None,
+            lossy: false,
+        }
+    }
+
+    fn lossy(out: &'a mut dyn std::fmt::Write) -> Self {
+        Self {
+            out,
+            invalid: None,
+            lossy: true,
+        }
+    }
+
+    fn write_string(
+        write: impl FnOnce(&mut MarkupWriter) -> std::fmt::Result,
+    ) -> Result<String, InvalidMarkupName> {
+        let mut markup = String::new();
+        let mut writer = MarkupWriter::new(&mut markup);
+        match write(&mut writer) {
+            Ok(()) => Ok(markup),
+            Err(_) => Err(writer
+                .invalid
+                .expect("writing to a `String` only fails for invalid names")),
+        }
+    }
+
+    // Names are written without escapes, so they must be a single word without
+    // special tokens. `unambiguous` rejects names that the parser reads
+    // differently where they are written.
+    fn write_name(&mut self, name: &str, unambiguous: bool) -> std::fmt::Result {
+        let is_word = !name.is_empty()
+            && !name.contains(|c: char| c.is_whitespace() || "`!,[]<>{}()\"|\\".contains(c));
+        if !is_word || !unambiguous {
+            self.invalid = Some(InvalidMarkupName(String::from(name)));
+            if self.lossy {
+                return write_text(self, name, true);
+            }
+            return Err(std::fmt::Error);
+        }
+
+        self.out.write_str(name)
+    }
+}
+
+impl std::fmt::Write for MarkupWriter<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.out.write_str(s)
+    }
+}
+
+// Characters that must be escaped with a backslash in raw text.
+fn is_escaped(c: char) -> bool {
+    matches!(c, '`' | '[' | ']' | '{' | '}' | '<' | '>' | '\\')
+}
+
+fn write_text(f: &mut MarkupWriter, text: &str, in_branch: bool) -> std::fmt::Result {
+    use std::fmt::Write;
+
+    for c in text.chars() {
+        if is_escaped(c) || (in_branch && c == '|') {
+            f.write_str("\\")?;
+        }
+        f.write_char(c)?;
+    }
+
+    Ok(())
+}
+
+// Arguments are quoted unless they are a single word without special tokens.
+// Writes nothing if there are no arguments.
+fn write_args(
+    f: &mut MarkupWriter,
+    args: &[Cow<'static, str>],
+    named_args: &[NamedArg],
+) -> std::fmt::Result {
+    use std::fmt::Write;
+
+    if args.is_empty() && named_args.is_empty() {
+        return Ok(());
+    }
+
+    f.write_str("(")?;
+    let named_args = named_args.iter().map(|arg| (Some(&arg.name), &arg.value));
+    let args = args.iter().map(|arg| (None, arg));
+    for (i, (name, value)) in args.chain(named_args).enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        if let Some(name) = name {
+            f.write_name(name, !name.contains('='))?;
+            f.write_str("=")?;
+        }
+        write_arg(f, value)?;
+    }
+    f.write_str(")")
+}
+
+fn write_arg(f: &mut MarkupWriter, arg: &str) -> std::fmt::Result {
+    use std::fmt::Write;
+
+    let is_bare = !arg.is_empty()
+        && !arg.contains(|c: char| c.is_whitespace() || c == '=' || "`![]<>{}(),|\"\\".contains(c));
+    if is_bare {
+        return f.write_str(arg);
+    }
+
+    f.write_str("\"")?;
+    for c in arg.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
 }
 
 pub(crate) fn pretty_text_spans(
@@ -244,9 +842,10 @@ pub(crate) fn pretty_text_spans(
 ///
 /// Useful for storing the entire sequence in a single
 /// [collection](PrettyTextSpans).
-#[derive(Debug, Clone, Reflect)]
+#[derive(Debug, Clone, PartialEq, Reflect)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(no_field_bounds)]
 pub enum TextSpanBundle {
     /// Span of text with optional modifiers.
     Span {
@@ -254,44 +853,248 @@ pub enum TextSpanBundle {
         span: Span,
         /// One or more [`Modifier`]s applied to `span`.
         mods: Modifiers,
+        /// Location of `span` in the parsed markup.
+        source: MarkupSource,
     },
     /// Type writer command.
     Effect(TypeWriterCommand),
-    /// Type writer event.
-    Event(TypeWriterEvent),
+    /// Type writer event with optional arguments for a
+    /// [dynamic event](crate::type_writer::dynamic_events).
+    Event(TypeWriterEvent, TypeWriterEventArgs),
     /// Type writer callback.
     Callback(
         #[cfg_attr(feature = "serialize", serde(skip))]
         #[cfg_attr(feature = "serialize", reflect(skip_serializing))]
         TypeWriterCallback,
     ),
+    /// Type writer callback registered by name.
+    NamedCallback(TypeWriterNamedCallback),
+    /// Bundles selected by a [variable](crate::variables#conditions) when spawned.
+    Conditional {
+        /// Name of the variable.
+        variable: Cow<'static, str>,
+        /// Spawned if the variable is truthy.
+        then: Vec<TextSpanBundle>,
+        /// Spawned if the variable is falsy.
+        otherwise: Vec<TextSpanBundle>,
+    },
+    /// Bundles selected by the plural form of a [number variable](crate::variables#conditions)
+    /// when spawned.
+    Plural {
+        /// Name of the variable.
+        variable: Cow<'static, str>,
+        /// The `[one, other]` or `[zero, one, other]` forms.
+        branches: Vec<Vec<TextSpanBundle>>,
+    },
 }
 
 impl TextSpanBundle {
     /// Spawn this bundle as a child of `entity`.
+    ///
+    /// [Conditions](crate::variables#conditions) are only selected when spawned
+    /// with [`PrettyTextSpans`].
     pub fn with_parent(self, entity: &mut EntityCommands) {
         spawn_bundle_with_parent(self, entity);
     }
+
+    /// Serialize the bundle back into markup.
+    ///
+    /// See [`PrettyTextSpans::to_markup`].
+    #[inline]
+    pub fn to_markup(&self) -> Result<String, InvalidMarkupName> {
+        MarkupWriter::write_string(|writer| self.write_markup(writer, false))
+    }
+}
+
+/// Writes markup, see [`TextSpanBundle::to_markup`].
+///
+/// A name that can not be written is written as escaped text, so the markup may
+/// not parse back into the same bundle.
+impl std::fmt::Display for TextSpanBundle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_markup(&mut MarkupWriter::lossy(f), false)
+    }
+}
+
+impl TextSpanBundle {
+    // Text directly within a condition's branch must escape `|`.
+    fn write_markup(&self, f: &mut MarkupWriter, in_branch: bool) -> std::fmt::Result {
+        use std::fmt::Write;
+
+        match self {
+            Self::Span { span, mods, .. } => {
+                // Spans without modifiers are written inline.
+                if mods.0.is_empty() {
+                    return span.write_markup(f, in_branch);
+                }
+
+                // "``[wave]" is not valid markup, and an empty span displays nothing.
+                if span.is_empty() {
+                    return Ok(());
+                }
+
+                f.write_str("`")?;
+                span.write_markup(f, false)?;
+                f.write_str("`[")?;
+                mods.write_markup(f)?;
+                f.write_str("]")
+            }
+            Self::Effect(TypeWriterCommand::Speed(speed)) => write!(f, "<{speed}>"),
+            Self::Effect(TypeWriterCommand::Pause(duration)) => write!(f, "[{duration}]"),
+            Self::Effect(TypeWriterCommand::WaitForInput) => write!(f, "[wait]"),
+            Self::Effect(TypeWriterCommand::WaitFor { signal, timeout }) => {
+                f.write_str("{wait:")?;
+                f.write_name(signal, !signal.contains(':'))?;
+                match timeout {
+                    Some(timeout) => write!(f, ":{timeout}}}"),
+                    None => f.write_str("}"),
+                }
+            }
+            Self::Event(event, args) => {
+                f.write_str("{")?;
+                // Other prefixes are parsed as variables, conditions and commands.
+                let tag = &event.0;
+                f.write_name(
+                    tag,
+                    !tag.starts_with(['?', '#', '$', '@'])
+                        && !tag.starts_with("wait:")
+                        && !tag.starts_with("img:"),
+                )?;
+                write_args(f, &args.args, &args.named_args)?;
+                f.write_str("}")
+            }
+            Self::Callback(_) => f.write_str("{}"),
+            Self::NamedCallback(callback) => {
+                f.write_str("{@")?;
+                f.write_name(&callback.0, true)?;
+                f.write_str("}")
+            }
+            Self::Conditional {
+                variable,
+                then,
+                otherwise,
+            } => {
+                f.write_str("{?")?;
+                f.write_name(variable, true)?;
+                let otherwise = (!otherwise.is_empty()).then_some(otherwise);
+                write_branches(f, std::iter::once(then).chain(otherwise))
+            }
+            Self::Plural { variable, branches } => {
+                f.write_str("{#")?;
+                f.write_name(variable, true)?;
+                write_branches(f, branches)
+            }
+        }
+    }
+}
+
+fn write_branches<'a>(
+    f: &mut MarkupWriter,
+    branches: impl IntoIterator<Item = &'a Vec<TextSpanBundle>>,
+) -> std::fmt::Result {
+    use std::fmt::Write;
+
+    for branch in branches {
+        f.write_str("|")?;
+        for bundle in branch.iter() {
+            bundle.write_markup(f, true)?;
+        }
+    }
+    f.write_str("}")
+}
+
+impl Span {
+    fn write_markup(&self, f: &mut MarkupWriter, in_branch: bool) -> std::fmt::Result {
+        use std::fmt::Write;
+
+        match self {
+            Self::Text(text) => write_text(f, text, in_branch),
+            Self::Variable(name) => {
+                f.write_str("{$")?;
+                f.write_name(name, true)?;
+                f.write_str("}")
+            }
+            Self::Image(name) => {
+                f.write_str("{img:")?;
+                f.write_name(name, true)?;
+                f.write_str("}")
+            }
+            Self::Bundles(bundles) => bundles
+                .iter()
+                .try_for_each(|bundle| bundle.write_markup(f, in_branch)),
+        }
+    }
+
+    // Whether the span contains no text, variables, images or commands.
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Text(text) => text.is_empty(),
+            Self::Variable(_) | Self::Image(_) => false,
+            Self::Bundles(bundles) => bundles.iter().all(
+                |bundle| matches!(bundle, TextSpanBundle::Span { span, .. } if span.is_empty()),
+            ),
+        }
+    }
 }
 
 /// Contains a range of text, denoted with backticks: ``"`...`"``.
 ///
 /// This range can contain either raw text or a collection of spans, allowing
 /// for recursive parsing.
-#[derive(Debug, Clone, Reflect)]
+#[derive(Debug, Clone, PartialEq, Reflect)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 #[reflect(no_field_bounds)]
 pub enum Span {
     /// Raw text data.
     Text(Cow<'static, str>),
+    /// A [variable](crate::variables) that is resolved when spawned.
+    Variable(Cow<'static, str>),
+    /// An [inline image](crate::inline_image).
+    Image(Cow<'static, str>),
     /// Recursive collection of spans.
     Bundles(Vec<TextSpanBundle>),
 }
 
+/// Location of a [`TextSpanBundle::Span`] in the markup it was parsed from.
+///
+/// Raw text spans cover their text, including escape characters. Spans with
+/// modifiers cover the backticks and modifiers. Spawned [`TextSpan`]s carry
+/// the `MarkupSource` of their text.
+///
+/// Spans that are not produced by the [`PrettyTextParser`] have an empty range.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+pub struct MarkupSource {
+    /// Byte range of the span in the markup.
+    pub range: Range<usize>,
+
+    /// Byte offsets in the markup of the backslashes that escape a character
+    /// of a raw text span, in ascending order.
+    pub escapes: Vec<usize>,
+}
+
+#[cfg(feature = "proc-macro")]
+impl quote::ToTokens for MarkupSource {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        use quote::TokenStreamExt;
+
+        let start = self.range.start;
+        let end = self.range.end;
+        let escapes = &self.escapes;
+        tokens.append_all(quote::quote! {
+            bevy_pretty_text::parser::MarkupSource {
+                range: #start..#end,
+                escapes: vec![#(#escapes),*],
+            }
+        });
+    }
+}
+
 /// A comma separated collection of [effects](crate::dynamic_effects) and [styles](crate::style)
 /// directly following a [`Span`], contained within square brackets: `"[mod1, ...]"`.
-#[derive(Debug, Default, Clone, Component, Reflect)]
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 pub struct Modifiers(pub Vec<Modifier>);
@@ -300,7 +1103,7 @@ pub struct Modifiers(pub Vec<Modifier>);
 /// [`Modifiers`]:
 /// - Effect -> `"name[(arg1, ...)]"`
 /// - Style  -> `"!name"`
-#[derive(Debug, Clone, Reflect)]
+#[derive(Debug, Clone, PartialEq, Reflect)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 pub enum Modifier {
@@ -310,6 +1113,61 @@ pub enum Modifier {
     Style(SpanStyle),
 }
 
+impl Modifiers {
+    fn write_markup(&self, f: &mut MarkupWriter) -> std::fmt::Result {
+        use std::fmt::Write;
+
+        for (i, modifier) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            modifier.write_markup(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Modifier {
+    fn write_markup(&self, f: &mut MarkupWriter) -> std::fmt::Result {
+        use std::fmt::Write;
+
+        match self {
+            Self::Effect(effect) => {
+                f.write_name(&effect.tag, true)?;
+                write_args(f, &effect.args, &effect.named_args)
+            }
+            Self::Style(SpanStyle::Style(style)) => {
+                f.write_str("!")?;
+                f.write_name(style, true)
+            }
+            Self::Style(SpanStyle::StyleSet(styles)) => {
+                for (i, style) in styles.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    f.write_str("!")?;
+                    f.write_name(style, true)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Modifiers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_markup(&mut MarkupWriter::lossy(f))
+    }
+}
+
+impl std::fmt::Display for Modifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_markup(&mut MarkupWriter::lossy(f))
+    }
+}
+
 #[cfg(feature = "proc-macro")]
 impl quote::ToTokens for Modifier {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
@@ -332,9 +1190,15 @@ impl quote::ToTokens for Modifier {
 
 fn spawn_bundle_with_parent(bundle: TextSpanBundle, entity: &mut EntityCommands) {
     match bundle {
-        TextSpanBundle::Span { span, mods } => match span {
+        TextSpanBundle::Span { span, mods, source } => match span {
             Span::Text(text) => {
-                entity.with_child((PrettyText, TextSpan::new(text), mods));
+                entity.with_child((PrettyText, TextSpan::new(text), mods, source));
+            }
+            Span::Variable(name) => {
+                entity.with_child((PrettyText, TextVariable(name), mods, source));
+            }
+            Span::Image(name) => {
+                entity.with_child((PrettyText, TextImage(name), mods, source));
             }
             Span::Bundles(bundles) => {
                 let mut mods = mods.0;
@@ -346,12 +1210,22 @@ fn spawn_bundle_with_parent(bundle: TextSpanBundle, entity: &mut EntityCommands)
         TextSpanBundle::Effect(effect) => {
             entity.with_child(effect);
         }
-        TextSpanBundle::Event(tag) => {
-            entity.with_child(tag);
+        TextSpanBundle::Event(tag, args) => {
+            if args.is_empty() {
+                entity.with_child(tag);
+            } else {
+                entity.with_child((tag, args));
+            }
         }
         TextSpanBundle::Callback(callback) => {
             entity.with_child(callback);
         }
+        TextSpanBundle::NamedCallback(callback) => {
+            entity.with_child(callback);
+        }
+        TextSpanBundle::Conditional { variable, .. } | TextSpanBundle::Plural { variable, .. } => {
+            error!("condition on `{variable}` must be spawned with `PrettyTextSpans`");
+        }
     }
 }
 
@@ -361,7 +1235,7 @@ fn spawn_bundle_with_parent_recur(
     parent_mods: &mut Vec<Modifier>,
 ) {
     match bundle {
-        TextSpanBundle::Span { span, mods } => match span {
+        TextSpanBundle::Span { span, mods, source } => match span {
             Span::Text(text) => {
                 let mut new_effects = mods.0;
                 if !parent_mods.is_empty() {
@@ -374,8 +1248,26 @@ fn spawn_bundle_with_parent_recur(
                     PrettyText,
                     TextSpan::new(text.as_ref()),
                     Modifiers(new_effects),
+                    source,
                 ));
             }
+            Span::Variable(name) => {
+                let mut new_effects = mods.0;
+                new_effects.extend(parent_mods.iter().cloned());
+
+                entity.with_child((
+                    PrettyText,
+                    TextVariable(name),
+                    Modifiers(new_effects),
+                    source,
+                ));
+            }
+            Span::Image(name) => {
+                let mut new_effects = mods.0;
+                new_effects.extend(parent_mods.iter().cloned());
+
+                entity.with_child((PrettyText, TextImage(name), Modifiers(new_effects), source));
+            }
             Span::Bundles(bundles) => {
                 let len = mods.0.len();
                 parent_mods.extend(mods.0);
@@ -392,12 +1284,22 @@ fn spawn_bundle_with_parent_recur(
         TextSpanBundle::Effect(effect) => {
             entity.with_child(effect);
         }
-        TextSpanBundle::Event(tag) => {
-            entity.with_child(tag);
+        TextSpanBundle::Event(tag, args) => {
+            if args.is_empty() {
+                entity.with_child(tag);
+            } else {
+                entity.with_child((tag, args));
+            }
         }
         TextSpanBundle::Callback(callback) => {
             entity.with_child(callback);
         }
+        TextSpanBundle::NamedCallback(callback) => {
+            entity.with_child(callback);
+        }
+        TextSpanBundle::Conditional { variable, .. } | TextSpanBundle::Plural { variable, .. } => {
+            error!("condition on `{variable}` must be spawned with `PrettyTextSpans`");
+        }
     }
 }
 
@@ -407,18 +1309,23 @@ mod sealed {
 
     use bevy::ecs::spawn::SpawnableList;
     use bevy::prelude::*;
-    use winnow::combinator::{alt, delimited, fail, opt, preceded, repeat, separated};
-    use winnow::error::{ContextError, ErrMode, ParserError, StrContext, StrContextValue};
+    use winnow::combinator::{
+        alt, cut_err, delimited, fail, opt, preceded, repeat, separated, terminated,
+    };
+    use winnow::error::{ContextError, ErrMode, ParserError};
     use winnow::stream::Stream;
     use winnow::token::take_while;
     use winnow::{Parser, prelude::*};
 
-    use crate::dynamic_effects::PrettyTextEffect;
+    use crate::dynamic_effects::{NamedArg, PrettyTextEffect};
     use crate::parser::{Modifier, Modifiers};
     use crate::style::SpanStyle;
-    use crate::type_writer::hierarchy::{TypeWriterCallback, TypeWriterCommand, TypeWriterEvent};
+    use crate::type_writer::hierarchy::{
+        TypeWriterCallback, TypeWriterCommand, TypeWriterEvent, TypeWriterEventArgs,
+        TypeWriterNamedCallback,
+    };
 
-    use super::{Span, TextSpanBundle};
+    use super::{MarkupSource, ParseErrorKind, PrettyTextParseError, Span, TextSpanBundle};
 
     pub(super) struct TextSpanSpawner(std::vec::IntoIter<TextSpanBundle>);
 
@@ -430,9 +1337,10 @@ mod sealed {
 
     impl SpawnableList<ChildOf> for TextSpanSpawner {
         fn spawn(self, world: &mut World, entity: Entity) {
+            let spans = crate::variables::select_branches(self.0.collect(), world);
             let mut commands = world.commands();
             let mut parent = commands.entity(entity);
-            for span in self.0 {
+            for span in spans {
                 span.with_parent(&mut parent);
             }
         }
@@ -442,44 +1350,138 @@ mod sealed {
         }
     }
 
-    pub(super) fn parse_bundles(pretty_text: &str) -> Result<Vec<TextSpanBundle>, String> {
-        tokenize
-            .parse(pretty_text)
-            .map_err(|err| err.to_string())
-            .and_then(|tokens| {
-                parse_tokens.parse(&tokens).map_err(|err| {
-                    pretty_print_token_err(pretty_text, err.input(), err.offset(), err.inner())
-                })
-            })
+    pub(super) fn parse_bundles(
+        pretty_text: &str,
+    ) -> Result<Vec<TextSpanBundle>, PrettyTextParseError> {
+        parse_with(pretty_text, parse_tokens)
+    }
+
+    pub(super) fn parse_bbcode(
+        pretty_text: &str,
+    ) -> Result<Vec<TextSpanBundle>, PrettyTextParseError> {
+        parse_with(pretty_text, bbcode_tokens)
     }
 
-    fn parse_tokens(input: &mut &[Token]) -> ModalResult<Vec<TextSpanBundle>> {
+    fn parse_with(
+        pretty_text: &str,
+        mut parser: impl for<'i, 'a> FnMut(&mut &'i [Token<'a>]) -> TokenResult<Vec<TextSpanBundle>>,
+    ) -> Result<Vec<TextSpanBundle>, PrettyTextParseError> {
+        let tokens = tokenize.parse(pretty_text).map_err(|err| {
+            let offset = err.offset();
+            let end = pretty_text[offset..]
+                .chars()
+                .next()
+                .map(|c| offset + c.len_utf8())
+                .unwrap_or(offset);
+
+            PrettyTextParseError {
+                input: pretty_text.to_string(),
+                span: offset..end,
+                token: (offset != end).then(|| pretty_text[offset..end].to_string()),
+                kind: ParseErrorKind::UnexpectedToken,
+                expected: Vec::new(),
+            }
+        })?;
+
+        let mut bundles = parser
+            .parse(&tokens)
+            .map_err(|err| token_err(pretty_text, err.input(), err.offset(), err.inner()))?;
+
+        let mut offsets = Vec::with_capacity(tokens.len() + 1);
+        let mut offset = 0;
+        offsets.push(offset);
+        for token in tokens.iter() {
+            offset += token.as_str().len();
+            offsets.push(offset);
+        }
+        resolve_sources(&mut bundles, &offsets);
+
+        Ok(bundles)
+    }
+
+    // While parsing, a `MarkupSource` holds the number of tokens remaining at
+    // the start and end of its span and at each escape. `offsets` maps token
+    // indices to bytes.
+    fn resolve_sources(bundles: &mut [TextSpanBundle], offsets: &[usize]) {
+        let len = offsets.len() - 1;
+        for bundle in bundles.iter_mut() {
+            match bundle {
+                TextSpanBundle::Span { span, source, .. } => {
+                    source.range =
+                        offsets[len - source.range.start]..offsets[len - source.range.end];
+                    for escape in source.escapes.iter_mut() {
+                        *escape = offsets[len - *escape];
+                    }
+                    if let Span::Bundles(bundles) = span {
+                        resolve_sources(bundles, offsets);
+                    }
+                }
+                TextSpanBundle::Conditional {
+                    then, otherwise, ..
+                } => {
+                    resolve_sources(then, offsets);
+                    resolve_sources(otherwise, offsets);
+                }
+                TextSpanBundle::Plural { branches, .. } => {
+                    for branch in branches.iter_mut() {
+                        resolve_sources(branch, offsets);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    enum ParseContext {
+        Kind(ParseErrorKind),
+        Expected(Cow<'static, str>),
+    }
+
+    type TokenError = ContextError<ParseContext>;
+    type TokenResult<O> = ModalResult<O, TokenError>;
+
+    fn kind(kind: ParseErrorKind) -> ParseContext {
+        ParseContext::Kind(kind)
+    }
+
+    fn expected(description: &'static str) -> ParseContext {
+        ParseContext::Expected(Cow::Borrowed(description))
+    }
+
+    fn parse_tokens(input: &mut &[Token]) -> TokenResult<Vec<TextSpanBundle>> {
         repeat::<_, _, Vec<_>, _, _>(1.., text_components).parse_next(input)
     }
 
-    fn text_components(input: &mut &[Token]) -> ModalResult<TextSpanBundle> {
+    fn text_components(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
         alt((
             speed,
             pause,
+            wait,
             normal_text,
             styled_effect_text,
             event,
-            fail.context(StrContext::Label("item")),
+            fail.context(expected("text, a span, or a type writer command")),
         ))
         .parse_next(input)
     }
 
-    fn speed(input: &mut &[Token]) -> ModalResult<TextSpanBundle> {
-        delimited(
+    fn speed(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
+        preceded(
             Token::OpenAngle,
-            token_str.verify_map(|value| value.parse::<f32>().ok()),
-            Token::CloseAngle,
+            cut_err(terminated(
+                token_str
+                    .verify_map(|value| value.parse::<f32>().ok())
+                    .context(expected("a speed multiplier, e.g. `<2>`")),
+                Token::CloseAngle.context(expected("`>`")),
+            ))
+            .context(kind(ParseErrorKind::InvalidCommand)),
         )
         .map(|speed| TextSpanBundle::Effect(TypeWriterCommand::Speed(speed)))
         .parse_next(input)
     }
 
-    fn pause(input: &mut &[Token]) -> ModalResult<TextSpanBundle> {
+    fn pause(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
         delimited(
             Token::OpenBracket,
             token_str.verify_map(|value| value.parse::<f32>().ok()),
@@ -489,66 +1491,246 @@ mod sealed {
         .parse_next(input)
     }
 
+    fn wait(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
+        delimited(
+            Token::OpenBracket,
+            token_str.verify(|value: &str| value == "wait"),
+            Token::CloseBracket,
+        )
+        .map(|_| TextSpanBundle::Effect(TypeWriterCommand::WaitForInput))
+        .parse_next(input)
+    }
+
+    // A piece of raw text, and the number of tokens remaining at its escape.
+    type TextPiece<'a> = (&'a str, Option<usize>);
+
     #[derive(Default)]
-    struct RawTextAccumulator<'a>(smallvec::SmallVec<[&'a str; 3]>);
+    struct RawTextAccumulator<'a> {
+        text: smallvec::SmallVec<[&'a str; 3]>,
+        escapes: Vec<usize>,
+    }
 
-    impl<'a> winnow::stream::Accumulate<&'a str> for RawTextAccumulator<'a> {
+    impl<'a> winnow::stream::Accumulate<TextPiece<'a>> for RawTextAccumulator<'a> {
         fn initial(capacity: Option<usize>) -> Self {
-            capacity
-                .map(|cap| Self(smallvec::SmallVec::with_capacity(cap)))
-                .unwrap_or_default()
+            Self {
+                text: capacity
+                    .map(smallvec::SmallVec::with_capacity)
+                    .unwrap_or_default(),
+                escapes: Vec::new(),
+            }
         }
 
-        fn accumulate(&mut self, acc: &'a str) {
-            self.0.push(acc);
+        fn accumulate(&mut self, (text, escape): TextPiece<'a>) {
+            self.text.push(text);
+            self.escapes.extend(escape);
         }
     }
 
-    fn raw_text(input: &mut &[Token]) -> ModalResult<Span> {
-        repeat(
-            1..,
-            alt((
-                token_str,
-                Token::Bang.map(|_| "!"),
-                Token::Comma.map(|_| ","),
-                Token::OpenParen.map(|_| "("),
-                Token::CloseParen.map(|_| ")"),
-                (Token::BackSlash, Token::BackTick).map(|_| "`"),
-                (Token::BackSlash, Token::OpenBracket).map(|_| "["),
-                (Token::BackSlash, Token::CloseBracket).map(|_| "]"),
-                (Token::BackSlash, Token::OpenCurly).map(|_| "{"),
-                (Token::BackSlash, Token::CloseCurly).map(|_| "}"),
-                (Token::BackSlash, Token::OpenAngle).map(|_| "<"),
-                (Token::BackSlash, Token::CloseAngle).map(|_| ">"),
-            )),
-        )
-        .map(|text: RawTextAccumulator| Span::Text(Cow::Owned(text.0.join(""))))
-        .parse_next(input)
+    impl RawTextAccumulator<'_> {
+        fn into_span(self, start: usize, end: usize) -> (Span, MarkupSource) {
+            (
+                Span::Text(Cow::Owned(self.text.join(""))),
+                MarkupSource {
+                    range: start..end,
+                    escapes: self.escapes,
+                },
+            )
+        }
     }
 
-    fn normal_text(input: &mut &[Token]) -> ModalResult<TextSpanBundle> {
-        raw_text
-            .map(|span| TextSpanBundle::Span {
-                span,
-                mods: Modifiers::default(),
-            })
-            .parse_next(input)
+    fn raw_text(input: &mut &[Token]) -> TokenResult<(Span, MarkupSource)> {
+        let start = input.len();
+        let text: RawTextAccumulator = repeat(1.., text_token).parse_next(input)?;
+        Ok(text.into_span(start, input.len()))
+    }
+
+    fn text_token<'a>(input: &mut &'a [Token]) -> TokenResult<TextPiece<'a>> {
+        alt((Token::Pipe.map(|_| ("|", None)), branch_text_token)).parse_next(input)
+    }
+
+    // Text within a condition's branch, where `|` separates branches.
+    fn branch_text_token<'a>(input: &mut &'a [Token]) -> TokenResult<TextPiece<'a>> {
+        let start = input.len();
+        let text = alt((
+            token_str,
+            Token::Bang.map(|_| "!"),
+            Token::Comma.map(|_| ","),
+            Token::OpenParen.map(|_| "("),
+            Token::CloseParen.map(|_| ")"),
+            Token::Quote.map(|_| "\""),
+            (Token::BackSlash, Token::BackTick).map(|_| "`"),
+            (Token::BackSlash, Token::BackSlash).map(|_| "\\"),
+            (Token::BackSlash, Token::OpenBracket).map(|_| "["),
+            (Token::BackSlash, Token::CloseBracket).map(|_| "]"),
+            (Token::BackSlash, Token::OpenCurly).map(|_| "{"),
+            (Token::BackSlash, Token::CloseCurly).map(|_| "}"),
+            (Token::BackSlash, Token::OpenAngle).map(|_| "<"),
+            (Token::BackSlash, Token::CloseAngle).map(|_| ">"),
+            (Token::BackSlash, Token::Pipe).map(|_| "|"),
+        ))
+        .parse_next(input)?;
+
+        // Escapes are the only pieces that span two tokens.
+        let escape = (start - input.len() == 2).then_some(start);
+        Ok((text, escape))
+    }
+
+    fn branch_components(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
+        alt((speed, pause, wait, branch_text, styled_effect_text, event)).parse_next(input)
+    }
+
+    fn bbcode_branch_components(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
+        alt((speed, pause, wait, branch_text, bbcode_tag, bbcode_event)).parse_next(input)
+    }
+
+    fn branch_text(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
+        let start = input.len();
+        let text: RawTextAccumulator = repeat(1.., branch_text_token).parse_next(input)?;
+        let (span, source) = text.into_span(start, input.len());
+
+        Ok(TextSpanBundle::Span {
+            span,
+            mods: Modifiers::default(),
+            source,
+        })
     }
 
-    fn styled_effect_text(input: &mut &[Token]) -> ModalResult<TextSpanBundle> {
+    fn normal_text(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
+        let (span, source) = raw_text.parse_next(input)?;
+
+        Ok(TextSpanBundle::Span {
+            span,
+            mods: Modifiers::default(),
+            source,
+        })
+    }
+
+    fn styled_effect_text(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
+        let start = input.len();
         (
             preceded(Token::BackTick, repeat(1.., text_components)),
             Token::BackTick,
-            delimited(Token::OpenBracket, mods, Token::CloseBracket),
+            cut_err(delimited(
+                Token::OpenBracket
+                    .context(kind(ParseErrorKind::MissingModifiers))
+                    .context(expected("modifiers, e.g. `[shake]`")),
+                mods,
+                Token::CloseBracket,
+            )),
         )
+            .parse_next(input)
             .map(|(bundles, _, mods)| TextSpanBundle::Span {
                 span: Span::Bundles(bundles),
                 mods,
+                source: MarkupSource {
+                    range: start..input.len(),
+                    escapes: Vec::new(),
+                },
             })
-            .parse_next(input)
     }
 
-    fn mods(input: &mut &[Token]) -> ModalResult<Modifiers> {
+    fn bbcode_tokens(input: &mut &[Token]) -> TokenResult<Vec<TextSpanBundle>> {
+        let bundles = repeat::<_, _, Vec<_>, _, _>(0.., bbcode_components).parse_next(input)?;
+
+        if close_tag.parse_peek(*input).is_ok() {
+            return cut_err(fail)
+                .context(kind(ParseErrorKind::UnexpectedCloseTag))
+                .parse_next(input);
+        }
+
+        if bundles.is_empty() {
+            return fail.parse_next(input);
+        }
+
+        Ok(bundles)
+    }
+
+    fn bbcode_components(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
+        alt((
+            speed,
+            pause,
+            wait,
+            normal_text,
+            bbcode_tag,
+            bbcode_event,
+            fail.context(expected("text, a tag, or a type writer command")),
+        ))
+        .parse_next(input)
+    }
+
+    fn bbcode_tag(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
+        let start = input.len();
+        let checkpoint = input.checkpoint();
+
+        Token::OpenBracket.parse_next(input)?;
+        if input.peek_token().is_some_and(
+            |token| matches!(token, Token::Text(str) if str.trim_start().starts_with('/')),
+        ) {
+            return fail.parse_next(input);
+        }
+
+        let mods = cut_err(terminated(mods, Token::CloseBracket)).parse_next(input)?;
+        let name = match &mods.0[0] {
+            Modifier::Effect(effect) => effect.tag.to_string(),
+            Modifier::Style(SpanStyle::Style(style)) => format!("!{style}"),
+            Modifier::Style(SpanStyle::StyleSet(_)) => unreachable!(),
+        };
+
+        let bundles = repeat(0.., bbcode_components).parse_next(input)?;
+
+        let close = input.checkpoint();
+        match close_tag.parse_next(input) {
+            Ok(tag) if tag.is_empty() || tag == name => Ok(TextSpanBundle::Span {
+                span: Span::Bundles(bundles),
+                mods,
+                source: MarkupSource {
+                    range: start..input.len(),
+                    escapes: Vec::new(),
+                },
+            }),
+            Ok(_) => {
+                input.reset(&close);
+                cut_err(fail)
+                    .context(kind(ParseErrorKind::MismatchedCloseTag))
+                    .context(ParseContext::Expected(Cow::Owned(format!("`[/{name}]`"))))
+                    .parse_next(input)
+            }
+            Err(_) => {
+                input.reset(&checkpoint);
+                cut_err(fail)
+                    .context(kind(ParseErrorKind::UnclosedTag))
+                    .context(ParseContext::Expected(Cow::Owned(format!("`[/{name}]`"))))
+                    .parse_next(input)
+            }
+        }
+    }
+
+    // Parses `[/name]`, `[/!name]`, or `[/]`, returning the trimmed name.
+    fn close_tag(input: &mut &[Token]) -> TokenResult<String> {
+        Token::OpenBracket.parse_next(input)?;
+        let mut name = token_str
+            .verify_map(|str| str.trim_start().strip_prefix('/'))
+            .map(|str| String::from(str.trim()))
+            .parse_next(input)?;
+
+        if name.is_empty() && opt(Token::Bang).parse_next(input)?.is_some() {
+            name = format!("!{}", token_str.parse_next(input)?.trim());
+        }
+
+        Token::CloseBracket.parse_next(input)?;
+        Ok(name)
+    }
+
+    fn mods(input: &mut &[Token]) -> TokenResult<Modifiers> {
+        if input
+            .peek_token()
+            .is_some_and(|token| matches!(token, Token::CloseBracket))
+        {
+            fail.context(kind(ParseErrorKind::EmptyModifiers))
+                .context(expected("a single modifier, e.g. `shake`"))
+                .parse_next(input)?;
+        }
+
         let mods = separated(
             1..,
             alt((
@@ -557,42 +1739,33 @@ mod sealed {
                     token_str
                         .verify(|str: &str| !str.trim().contains(char::is_whitespace))
                         .map(|str| String::from(str.trim()))
-                        .context(StrContext::Label("modifier"))
-                        .context(StrContext::Expected(StrContextValue::Description(
-                            "a single modifier, e.g. `shake`",
-                        ))),
+                        .context(expected("a single style, e.g. `!red`")),
                 )
                 .map(|str| Modifier::Style(SpanStyle::Style(Cow::Owned(str)))),
                 (
                     token_str
                         .verify(|str: &str| !str.trim().contains(char::is_whitespace))
                         .map(|str| Cow::Owned(String::from(str.trim())))
-                        .context(StrContext::Label("modifier"))
-                        .context(StrContext::Expected(StrContextValue::Description(
-                            "a single modifier, e.g. `shake`",
-                        ))),
-                    opt(delimited(
+                        .context(expected("a single modifier, e.g. `shake`")),
+                    opt(preceded(
                         Token::OpenParen,
-                        separated(
-                            1..,
-                            token_str
-                                .verify(|str: &str| !str.trim().contains(char::is_whitespace))
-                                .map(|str| Cow::Owned(String::from(str.trim())))
-                                .context(StrContext::Label("effect argument"))
-                                .context(StrContext::Expected(StrContextValue::Description(
-                                    "comma separated list",
-                                ))),
-                            Token::Comma,
-                        )
-                        .map_err(ErrMode::cut),
-                        Token::CloseParen
-                            .map_err(ErrMode::cut)
-                            .context(StrContext::Label("closing delimiter")),
+                        cut_err(terminated(
+                            effect_args,
+                            Token::CloseParen.context(expected("`)`")),
+                        ))
+                        .context(kind(ParseErrorKind::InvalidEffectArgs)),
                     ))
-                    .map(|args: Option<Vec<Cow<'static, str>>>| args.unwrap_or_default()),
+                    .map(|args| args.unwrap_or_default()),
                 )
-                    .map(|(tag, args)| Modifier::Effect(PrettyTextEffect { tag, args })),
-            )),
+                    .map(|(tag, (args, named_args))| {
+                        Modifier::Effect(PrettyTextEffect {
+                            tag,
+                            args,
+                            named_args,
+                        })
+                    }),
+            ))
+            .context(kind(ParseErrorKind::InvalidModifier)),
             (
                 Token::Comma,
                 opt(token_str.verify(|str: &str| str.chars().all(char::is_whitespace))),
@@ -604,41 +1777,368 @@ mod sealed {
             .peek_token()
             .is_none_or(|token| !matches!(token, Token::CloseBracket))
         {
-            fail.map_err(ErrMode::cut)
-                .context(StrContext::Label("text modifiers"))
-                .context(StrContext::Expected(StrContextValue::Description(
-                    "comma separated list",
-                )))
+            fail.context(kind(ParseErrorKind::UnclosedModifiers))
+                .context(expected("`,`"))
+                .context(expected("`]`"))
                 .parse_next(input)?;
         }
 
         Ok(Modifiers(mods))
     }
 
-    fn event(input: &mut &[Token]) -> ModalResult<TextSpanBundle> {
-        delimited(Token::OpenCurly, opt(token_str), Token::CloseCurly)
-            .map(|tag| {
-                tag.map(|tag| TextSpanBundle::Event(TypeWriterEvent(tag.to_string())))
-                    .unwrap_or_else(|| TextSpanBundle::Callback(TypeWriterCallback::default()))
-            })
-            .parse_next(input)
+    type EffectArgs = (Vec<Cow<'static, str>>, Vec<NamedArg>);
+
+    // Positional arguments must precede named arguments.
+    fn effect_args(input: &mut &[Token]) -> TokenResult<EffectArgs> {
+        let mut args = Vec::new();
+        let mut named_args = Vec::<NamedArg>::new();
+
+        loop {
+            let checkpoint = input.checkpoint();
+            match effect_arg.parse_next(input)? {
+                (None, value) => {
+                    if !named_args.is_empty() {
+                        input.reset(&checkpoint);
+                        return fail
+                            .context(expected("a named argument, e.g. `max_height=2`"))
+                            .parse_next(input);
+                    }
+
+                    args.push(value);
+                }
+                (Some(name), value) => {
+                    if named_args.iter().any(|arg| arg.name == name) {
+                        input.reset(&checkpoint);
+                        return fail
+                            .context(expected("a unique argument name"))
+                            .parse_next(input);
+                    }
+
+                    named_args.push(NamedArg { name, value });
+                }
+            }
+
+            if opt(Token::Comma).parse_next(input)?.is_none() {
+                return Ok((args, named_args));
+            }
+        }
+    }
+
+    fn effect_arg(
+        input: &mut &[Token],
+    ) -> TokenResult<(Option<Cow<'static, str>>, Cow<'static, str>)> {
+        alt((
+            (
+                opt(token_str.verify_map(|str| {
+                    if str.trim().is_empty() {
+                        return Some(None);
+                    }
+
+                    let (name, value) = str.split_once('=')?;
+                    let name = name.trim();
+                    (is_single_word(name) && value.trim().is_empty())
+                        .then(|| Some(Cow::Owned(String::from(name))))
+                })),
+                quoted_arg,
+                opt(token_str.verify(|str: &str| str.trim().is_empty())),
+            )
+                .map(|(name, value, _)| (name.flatten(), value)),
+            token_str.verify_map(|str| match str.split_once('=') {
+                Some((name, value)) => {
+                    let (name, value) = (name.trim(), value.trim());
+                    (is_single_word(name) && is_single_word(value)).then(|| {
+                        (
+                            Some(Cow::Owned(String::from(name))),
+                            Cow::Owned(String::from(value)),
+                        )
+                    })
+                }
+                None => {
+                    is_single_word(str.trim()).then(|| (None, Cow::Owned(String::from(str.trim()))))
+                }
+            }),
+        ))
+        .context(expected("a comma separated list of arguments"))
+        .parse_next(input)
+    }
+
+    // Quoted arguments may contain any token, with `"` and `\` escaped.
+    fn quoted_arg(input: &mut &[Token]) -> TokenResult<Cow<'static, str>> {
+        Token::Quote.parse_next(input)?;
+
+        let mut value = String::new();
+        loop {
+            match input.peek_token() {
+                Some(Token::Quote) => {
+                    input.next_token();
+                    return Ok(Cow::Owned(value));
+                }
+                Some(Token::BackSlash) => {
+                    input.next_token();
+                    match input.peek_token() {
+                        Some(Token::Quote) => value.push('"'),
+                        Some(Token::BackSlash) => value.push('\\'),
+                        Some(Token::Text(str)) if str.starts_with('n') => {
+                            value.push('\n');
+                            value.push_str(&str[1..]);
+                        }
+                        Some(Token::Text(str)) if str.starts_with('t') => {
+                            value.push('\t');
+                            value.push_str(&str[1..]);
+                        }
+                        _ => {
+                            return cut_err(fail)
+                                .context(kind(ParseErrorKind::InvalidEscape))
+                                .context(expected("an escape sequence, e.g. `\\\"`"))
+                                .parse_next(input);
+                        }
+                    }
+                    input.next_token();
+                }
+                Some(token) => {
+                    input.next_token();
+                    value.push_str(token.as_str());
+                }
+                None => {
+                    return cut_err(fail)
+                        .context(expected("a closing `\"`"))
+                        .parse_next(input);
+                }
+            }
+        }
+    }
+
+    type Components = fn(&mut &[Token]) -> TokenResult<TextSpanBundle>;
+
+    fn event(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
+        event_with(input, branch_components)
     }
 
-    fn token_str<'a>(input: &mut &'a [Token]) -> ModalResult<&'a str> {
-        match input.next_token() {
-            Some(Token::Text(str)) => Ok(str),
+    fn bbcode_event(input: &mut &[Token]) -> TokenResult<TextSpanBundle> {
+        event_with(input, bbcode_branch_components)
+    }
+
+    // `branch` parses the components of a condition's branches.
+    fn event_with(input: &mut &[Token], branch: Components) -> TokenResult<TextSpanBundle> {
+        let start = input.len();
+        let tag = (Token::OpenCurly, token_str)
+            .parse_peek(*input)
+            .map(|(_, (_, tag))| tag.trim_start())
+            .unwrap_or_default();
+
+        if tag.starts_with(['?', '#']) {
+            return (|input: &mut &[Token]| condition(input, branch))
+                .context(kind(ParseErrorKind::InvalidCondition))
+                .parse_next(input);
+        }
+
+        if tag.starts_with('$') {
+            let name = prefixed_tag(
+                input,
+                "$",
+                |name| is_single_word(name).then(|| Cow::Owned(String::from(name))),
+                "a variable name, e.g. `{$name}`",
+                ParseErrorKind::InvalidVariable,
+            )?;
+
+            return Ok(TextSpanBundle::Span {
+                span: Span::Variable(name),
+                mods: Modifiers::default(),
+                source: MarkupSource {
+                    range: start..input.len(),
+                    escapes: Vec::new(),
+                },
+            });
+        }
+
+        if tag.starts_with('@') {
+            let name = prefixed_tag(
+                input,
+                "@",
+                |name| is_single_word(name).then(|| String::from(name)),
+                "a callback name, e.g. `{@name}`",
+                ParseErrorKind::InvalidCallback,
+            )?;
+
+            return Ok(TextSpanBundle::NamedCallback(TypeWriterNamedCallback(name)));
+        }
+
+        if tag.starts_with("wait:") {
+            let command = prefixed_tag(
+                input,
+                "wait:",
+                |tag| {
+                    let tag = tag.trim();
+                    let (signal, timeout) = match tag.split_once(':') {
+                        Some((signal, timeout)) => {
+                            let timeout = timeout.trim().parse::<f32>().ok()?;
+                            if !timeout.is_finite() || timeout < 0. {
+                                return None;
+                            }
+                            (signal.trim(), Some(timeout))
+                        }
+                        None => (tag, None),
+                    };
+                    is_single_word(signal).then(|| TypeWriterCommand::WaitFor {
+                        signal: String::from(signal),
+                        timeout,
+                    })
+                },
+                "a signal, e.g. `{wait:door_open}`",
+                ParseErrorKind::InvalidCommand,
+            )?;
+
+            return Ok(TextSpanBundle::Effect(command));
+        }
+
+        if tag.starts_with("img:") {
+            let name = prefixed_tag(
+                input,
+                "img:",
+                |name| {
+                    let name = name.trim();
+                    is_single_word(name).then(|| Cow::Owned(String::from(name)))
+                },
+                "an image name, e.g. `{img:name}`",
+                ParseErrorKind::InvalidImage,
+            )?;
+
+            return Ok(TextSpanBundle::Span {
+                span: Span::Image(name),
+                mods: Modifiers::default(),
+                source: MarkupSource {
+                    range: start..input.len(),
+                    escapes: Vec::new(),
+                },
+            });
+        }
+
+        preceded(
+            Token::OpenCurly,
+            cut_err(terminated(
+                opt((
+                    token_str,
+                    opt(preceded(
+                        Token::OpenParen,
+                        cut_err(terminated(
+                            effect_args,
+                            Token::CloseParen.context(expected("`)`")),
+                        )),
+                    )),
+                )),
+                Token::CloseCurly.context(expected("`}`")),
+            ))
+            .context(kind(ParseErrorKind::InvalidEvent)),
+        )
+        .map(|event| match event {
+            Some((tag, args)) => {
+                let (args, named_args) = args.unwrap_or_default();
+                TextSpanBundle::Event(
+                    TypeWriterEvent(tag.to_string()),
+                    TypeWriterEventArgs { args, named_args },
+                )
+            }
+            None => TextSpanBundle::Callback(TypeWriterCallback::default()),
+        })
+        .parse_next(input)
+    }
+
+    // Parses `{<prefix>...}`, where `map` reads the text after `prefix`.
+    fn prefixed_tag<O>(
+        input: &mut &[Token],
+        prefix: &str,
+        map: impl Fn(&str) -> Option<O>,
+        description: &'static str,
+        error: ParseErrorKind,
+    ) -> TokenResult<O> {
+        delimited(
+            Token::OpenCurly,
+            cut_err(
+                token_str
+                    .verify_map(|tag| map(tag.trim().strip_prefix(prefix)?))
+                    .context(expected(description)),
+            ),
+            cut_err(Token::CloseCurly.context(expected("`}`"))),
+        )
+        .context(kind(error))
+        .parse_next(input)
+    }
+
+    // Names, e.g. of variables, signals and named arguments, are a single word.
+    fn is_single_word(name: &str) -> bool {
+        !name.is_empty() && !name.contains(char::is_whitespace)
+    }
+
+    fn condition(input: &mut &[Token], branch: Components) -> TokenResult<TextSpanBundle> {
+        Token::OpenCurly.parse_next(input)?;
+        let (plural, variable) = cut_err(
+            token_str
+                .verify_map(|tag| {
+                    let tag = tag.trim();
+                    let (plural, name) = match tag.strip_prefix('?') {
+                        Some(name) => (false, name),
+                        None => (true, tag.strip_prefix('#')?),
+                    };
+                    is_single_word(name).then(|| (plural, Cow::Owned(String::from(name))))
+                })
+                .context(expected("a variable name, e.g. `{?has_key|...}`")),
+        )
+        .parse_next(input)?;
+
+        let branches: Vec<Vec<TextSpanBundle>> = cut_err(
+            repeat(
+                1..,
+                preceded(Token::Pipe, repeat::<_, _, Vec<_>, _, _>(0.., branch)),
+            )
+            .context(expected("`|`")),
+        )
+        .parse_next(input)?;
+
+        let max = if plural { 3 } else { 2 };
+        if branches.len() > max || (plural && branches.len() < 2) {
+            return cut_err(fail)
+                .context(expected(if plural {
+                    "two or three plural forms, e.g. `{#count|apple|apples}`"
+                } else {
+                    "one or two branches, e.g. `{?has_key|unlocked|locked}`"
+                }))
+                .parse_next(input);
+        }
+        cut_err(Token::CloseCurly.context(expected("`}`"))).parse_next(input)?;
+
+        Ok(if plural {
+            TextSpanBundle::Plural { variable, branches }
+        } else {
+            let mut branches = branches.into_iter();
+            TextSpanBundle::Conditional {
+                variable,
+                then: branches.next().unwrap_or_default(),
+                otherwise: branches.next().unwrap_or_default(),
+            }
+        })
+    }
+
+    fn token_str<'a>(input: &mut &'a [Token]) -> TokenResult<&'a str> {
+        match input.peek_token() {
+            Some(Token::Text(str)) => {
+                input.next_token();
+                Ok(str)
+            }
             _ => fail.parse_next(input),
         }
     }
 
-    impl<'a> Parser<&[Token<'a>], Token<'a>, ErrMode<ContextError>> for Token<'a> {
+    impl<'a> Parser<&[Token<'a>], Token<'a>, ErrMode<TokenError>> for Token<'a> {
         fn parse_next(
             &mut self,
             input: &mut &[Token<'a>],
-        ) -> winnow::Result<Token<'a>, ErrMode<ContextError>> {
-            (input.next_token() == Some(*self))
-                .then_some(*self)
-                .ok_or_else(|| ParserError::from_input(input))
+        ) -> winnow::Result<Token<'a>, ErrMode<TokenError>> {
+            if input.peek_token() == Some(*self) {
+                input.next_token();
+                Ok(*self)
+            } else {
+                Err(ParserError::from_input(input))
+            }
         }
     }
 
@@ -656,6 +2156,8 @@ mod sealed {
         CloseCurly,
         OpenParen,
         CloseParen,
+        Quote,
+        Pipe,
         BackSlash,
     }
 
@@ -681,6 +2183,8 @@ mod sealed {
                 Self::CloseCurly => "}",
                 Self::OpenParen => "(",
                 Self::CloseParen => ")",
+                Self::Quote => "\"",
+                Self::Pipe => "|",
                 Self::BackSlash => "\\",
             }
         }
@@ -691,7 +2195,9 @@ mod sealed {
     }
 
     fn token<'a>(input: &mut &'a str) -> ModalResult<Token<'a>> {
-        let special_tokens = ['`', '!', '[', ']', '<', '>', '{', '}', '(', ')', ',', '\\'];
+        let special_tokens = [
+            '`', '!', '[', ']', '<', '>', '{', '}', '(', ')', ',', '"', '|', '\\',
+        ];
 
         alt((
             "`".map(|_| Token::BackTick),
@@ -705,47 +2211,81 @@ mod sealed {
             "(".map(|_| Token::OpenParen),
             ")".map(|_| Token::CloseParen),
             ",".map(|_| Token::Comma),
+            "\"".map(|_| Token::Quote),
+            "|".map(|_| Token::Pipe),
             "\\".map(|_| Token::BackSlash),
             take_while(1.., |c| !special_tokens.contains(&c)).map(Token::Text),
         ))
         .parse_next(input)
     }
 
-    fn pretty_print_token_err(
+    fn token_err(
         str_input: &str,
         input: &[Token],
         offset: usize,
-        ctx: &ContextError,
-    ) -> String {
-        let mut arrow_str = String::new();
-        if offset >= input.len() {
-            for _ in 0..str_input.len() {
-                arrow_str.push(' ');
-            }
-            arrow_str.push('^');
-        } else {
-            let location = input[offset];
-            let offset = input
-                .iter()
-                .map(|token| token.as_str().chars().count())
-                .take(offset)
-                .sum();
-
-            for _ in 0..offset {
-                arrow_str.push(' ');
+        ctx: &TokenError,
+    ) -> PrettyTextParseError {
+        let token = input.get(offset).copied();
+        let start = input
+            .iter()
+            .take(offset)
+            .map(|token| token.as_str().len())
+            .sum::<usize>();
+        let end = start + token.map(|token| token.as_str().len()).unwrap_or(0);
+
+        // The innermost kind is the most specific.
+        let kind = ctx
+            .context()
+            .find_map(|ctx| match ctx {
+                ParseContext::Kind(kind) => Some(*kind),
+                _ => None,
+            })
+            .unwrap_or(match token {
+                None => ParseErrorKind::EmptyInput,
+                Some(Token::BackTick) => ParseErrorKind::UnclosedSpan,
+                Some(Token::OpenBracket) => ParseErrorKind::InvalidCommand,
+                Some(Token::BackSlash) => ParseErrorKind::InvalidEscape,
+                Some(Token::CloseBracket | Token::CloseAngle | Token::CloseCurly) => {
+                    ParseErrorKind::UnmatchedDelimiter
+                }
+                Some(_) => ParseErrorKind::UnexpectedToken,
+            });
+
+        let mut expected = Vec::new();
+        for ctx in ctx.context() {
+            if let ParseContext::Expected(description) = ctx {
+                if !expected.contains(description) {
+                    expected.push(description.clone());
+                }
             }
-            for _ in offset..offset + location.as_str().chars().count() {
-                arrow_str.push('^');
+        }
+
+        if expected.is_empty() {
+            if let Some(description) = match kind {
+                ParseErrorKind::EmptyInput => Some("text, a span, or a type writer command"),
+                ParseErrorKind::UnclosedSpan => Some("a closing backtick"),
+                ParseErrorKind::InvalidCommand => Some("a pause in seconds, e.g. `[1.5]`"),
+                ParseErrorKind::InvalidEscape => Some("an escaped special token, e.g. `\\[`"),
+                _ => None,
+            } {
+                expected.push(Cow::Borrowed(description));
             }
         }
 
-        format!("failed to parse input\n{str_input}\n{arrow_str}\n{ctx}")
+        PrettyTextParseError {
+            input: str_input.to_string(),
+            span: start..end,
+            token: token.map(|token| token.as_str().to_string()),
+            kind,
+            expected,
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::dynamic_effects::NamedArg;
 
     #[track_caller]
     fn assert_ok(str: &str) {
@@ -768,6 +2308,10 @@ mod test {
         assert_ok("`simple{tag} tag and effect`[shake]");
 
         assert_ok("`effect args`[shake(1, \"str\", 9.232)]");
+        assert_ok("`named args`[wave(max_height=20)]");
+        assert_ok("`mixed args`[wave(1, max_height = 20)]");
+        assert_ok("`quoted args`[sound(\"door, creak\", name = \"a [b]\")]");
+        assert_ok("\"quoted\" `text`[!red]");
 
         assert_ok("`recursive `effect`[wave]`[shake]");
         assert_ok("`recursive `effect`[wave] and `style`[!red]`[shake]");
@@ -787,6 +2331,11 @@ mod test {
         assert_err("`no comma`[wave(1 2)]");
         assert_err("`many commas`[wave(1,, 2)]");
         assert_err("`empty mods`[]");
+        assert_err("`named then positional`[wave(max_height=20, 1)]");
+        assert_err("`duplicate names`[wave(a=1, a=2)]");
+        assert_err("`missing value`[wave(a=)]");
+        assert_err("`unclosed quote`[sound(\"door)]");
+        assert_err("`trailing text`[sound(\"door\" creak)]");
 
         assert_err("`wave and scramble`[wave(1, 20) scramble][2]");
 
@@ -798,4 +2347,613 @@ mod test {
 
         assert_err("{`styled`[!red]}");
     }
+
+    #[test]
+    fn named_effect_args() {
+        let spans = PrettyTextParser::spans("`text`[wave(1, max_height = 20)]").unwrap();
+        let TextSpanBundle::Span { mods, .. } = &spans.0[0] else {
+            panic!("expected span");
+        };
+        let Modifier::Effect(effect) = &mods.0[0] else {
+            panic!("expected effect");
+        };
+
+        assert_eq!(effect.tag, "wave");
+        assert_eq!(effect.args, vec![Cow::Borrowed("1")]);
+        assert_eq!(effect.named_args, vec![NamedArg::new("max_height", "20")]);
+    }
+
+    #[test]
+    fn quoted_effect_args() {
+        let spans = PrettyTextParser::spans(
+            r#"`text`[sound(1, "hello, world", "say \"hi\"\\\n", name = "(a) `b`")]"#,
+        )
+        .unwrap();
+        let TextSpanBundle::Span { mods, .. } = &spans.0[0] else {
+            panic!("expected span");
+        };
+        let Modifier::Effect(effect) = &mods.0[0] else {
+            panic!("expected effect");
+        };
+
+        assert_eq!(
+            effect.args,
+            vec![
+                Cow::Borrowed("1"),
+                Cow::Borrowed("hello, world"),
+                Cow::Borrowed("say \"hi\"\\\n"),
+            ]
+        );
+        assert_eq!(effect.named_args, vec![NamedArg::new("name", "(a) `b`")]);
+
+        let spans = PrettyTextParser::spans(r#"say "hi""#).unwrap();
+        assert!(matches!(
+            spans.0.as_slice(),
+            [TextSpanBundle::Span { span: Span::Text(text), .. }] if text == r#"say "hi""#
+        ));
+    }
+
+    #[test]
+    fn markup_source() {
+        let str = "a\\[b\\] `nested `ツ`[!red]`[wave] {event}c";
+        let spans = PrettyTextParser::spans(str).unwrap();
+
+        let sources = spans
+            .0
+            .iter()
+            .filter_map(|bundle| match bundle {
+                TextSpanBundle::Span { source, .. } => Some(&str[source.range.clone()]),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sources,
+            vec!["a\\[b\\] ", "`nested `ツ`[!red]`[wave]", " ", "c"]
+        );
+
+        let mut world = World::new();
+        world.spawn(spans.into_bundle());
+        world.flush();
+
+        let mut sources = world
+            .query::<(&TextSpan, &MarkupSource)>()
+            .iter(&world)
+            .map(|(span, source)| (span.0.clone(), &str[source.range.clone()]))
+            .collect::<Vec<_>>();
+        sources.sort();
+        assert_eq!(
+            sources,
+            vec![
+                (String::from(" "), " "),
+                (String::from("a[b] "), "a\\[b\\] "),
+                (String::from("c"), "c"),
+                (String::from("nested "), "nested "),
+                (String::from("ツ"), "ツ"),
+            ]
+        );
+    }
+
+    #[test]
+    fn plain_text_offsets() {
+        let str = "a\\[b `ツ`[wave]{event}c";
+        let plain = PrettyTextParser::spans(str).unwrap().plain_text();
+
+        assert_eq!(plain.as_str(), "a[b ツc");
+        assert_eq!(plain.markup_offset(0), Some(0));
+        assert_eq!(plain.markup_offset(1), Some(1));
+        assert_eq!(plain.markup_offset(2), Some(3));
+        assert_eq!(plain.markup_offset(4), Some(6));
+        assert_eq!(plain.markup_offset(7), Some(23));
+        assert_eq!(plain.markup_offset(8), Some(24));
+        assert_eq!(plain.markup_offset(9), None);
+
+        assert_eq!(plain.markup_range(1..2), Some(1..3));
+        assert_eq!(plain.markup_range(4..7), Some(6..9));
+        assert_eq!(&str[plain.markup_range(0..8).unwrap()], str);
+        assert_eq!(plain.markup_range(8..8), Some(24..24));
+
+        // Only backslashes that escape a character are skipped.
+        let str = "\\|a|b\\]";
+        let plain = PrettyTextParser::spans(str).unwrap().plain_text();
+
+        assert_eq!(plain.as_str(), "|a|b]");
+        assert_eq!(
+            (0..=5)
+                .map(|offset| plain.markup_offset(offset).unwrap())
+                .collect::<Vec<_>>(),
+            [0, 2, 3, 4, 5, 7]
+        );
+        assert_eq!(plain.markup_range(4..5), Some(5..7));
+    }
+
+    #[track_caller]
+    fn assert_round_trip(str: &str, canonical: &str) {
+        let spans = PrettyTextParser::spans(str).unwrap();
+        assert_eq!(spans.to_markup().unwrap(), canonical);
+
+        let spans = PrettyTextParser::spans(canonical).unwrap();
+        assert_eq!(spans.to_markup().unwrap(), canonical);
+        assert_eq!(
+            PrettyTextParser::spans(&spans.to_markup().unwrap()).unwrap(),
+            spans
+        );
+    }
+
+    #[test]
+    fn markup_round_trip() {
+        for str in [
+            "hello, world!",
+            "escaped \\` \\[\\] \\{\\} \\<\\> \\\\ ticks",
+            "\"quoted\" (text)!",
+            "`simple style and effect`[!red, shake]",
+            "`effect args`[shake(1, str, 9.232)]",
+            "`named args`[wave(1, max_height=20)]",
+            "`quoted args`[sound(\"door, \\\"creak\\\"\\n\", name=\"a [b]\", empty=\"\")]",
+            "`recursive `effect`[wave] and `style`[!red]`[shake]",
+            "<2.5>fast[1]pause{event}event",
+            "`{$name}`[wave] has {$gold} gold",
+            "`ツ`[wave]\n`multi\nline`[!red]",
+            "{?has_key|You `unlock`[shake] it.|It's locked.}",
+            "{?has_key|unlocked} a | b",
+            "{#apples|no apples|an apple|{$apples} apples}",
+            "{#apples|one \\|{?ripe|ripe}|{tag}other}",
+            "Press {img:button_a} to `{img:star}`[wave]",
+        ] {
+            assert_round_trip(str, str);
+        }
+
+        assert_round_trip(
+            "`messy`[wave( 1,max_height = 20 ), !red]",
+            "`messy`[wave(1, max_height=20), !red]",
+        );
+        assert_round_trip(
+            "`quoted words`[shake(\"str\")]",
+            "`quoted words`[shake(str)]",
+        );
+        assert_round_trip("<2.0>[0.50]", "<2>[0.5]");
+        assert_round_trip("a \\| b", "a | b");
+
+        // Callbacks are only equal to their clones.
+        let spans = PrettyTextParser::spans("{}callback").unwrap();
+        assert_eq!(spans.to_markup().unwrap(), "{}callback");
+    }
+
+    #[test]
+    fn invalid_markup_names() {
+        fn event(tag: &'static str) -> TextSpanBundle {
+            TextSpanBundle::Event(TypeWriterEvent::new(tag), TypeWriterEventArgs::default())
+        }
+
+        for (bundle, name) in [
+            (event("two words"), "two words"),
+            (event("?var"), "?var"),
+            (event("wait:signal"), "wait:signal"),
+            (event(""), ""),
+            (
+                TextSpanBundle::Effect(TypeWriterCommand::WaitFor {
+                    signal: String::from("a:b"),
+                    timeout: None,
+                }),
+                "a:b",
+            ),
+            (
+                TextSpanBundle::NamedCallback(TypeWriterNamedCallback::new("{x}")),
+                "{x}",
+            ),
+            (
+                TextSpanBundle::Span {
+                    span: Span::Image("a|b".into()),
+                    mods: Modifiers::default(),
+                    source: MarkupSource::default(),
+                },
+                "a|b",
+            ),
+            (
+                TextSpanBundle::Span {
+                    span: Span::Text("text".into()),
+                    mods: Modifiers(vec![Modifier::Style(SpanStyle::Style("red]".into()))]),
+                    source: MarkupSource::default(),
+                },
+                "red]",
+            ),
+            (
+                TextSpanBundle::Span {
+                    span: Span::Text("text".into()),
+                    mods: Modifiers(vec![Modifier::Effect(PrettyTextEffect {
+                        tag: "wave".into(),
+                        args: Vec::new(),
+                        named_args: vec![NamedArg::new("a=b", "1")],
+                    })]),
+                    source: MarkupSource::default(),
+                },
+                "a=b",
+            ),
+        ] {
+            assert_eq!(
+                bundle.to_markup(),
+                Err(InvalidMarkupName(String::from(name)))
+            );
+            // Formatting never fails.
+            let _ = bundle.to_string();
+            let _ = PrettyTextSpans(vec![bundle]).to_string();
+        }
+    }
+
+    fn strip_sources(bundles: &mut [TextSpanBundle]) {
+        for bundle in bundles.iter_mut() {
+            if let TextSpanBundle::Span { span, source, .. } = bundle {
+                *source = MarkupSource::default();
+                if let Span::Bundles(bundles) = span {
+                    strip_sources(bundles);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn constructed_markup_round_trip() {
+        fn span(span: Span, mods: Vec<Modifier>) -> TextSpanBundle {
+            TextSpanBundle::Span {
+                span,
+                mods: Modifiers(mods),
+                source: MarkupSource::default(),
+            }
+        }
+
+        let effect = Modifier::Effect(PrettyTextEffect {
+            tag: "sound".into(),
+            args: vec!["".into(), "a=b".into(), "1.5".into()],
+            named_args: vec![NamedArg::new("name", "door \"creak\"\t\\")],
+        });
+        let spans = PrettyTextSpans(vec![
+            span(Span::Text("`[]{}<>\\ \"text\"".into()), Vec::new()),
+            TextSpanBundle::Effect(TypeWriterCommand::Speed(0.1)),
+            span(
+                Span::Bundles(vec![
+                    span(Span::Text("nested ".into()), Vec::new()),
+                    span(
+                        Span::Bundles(vec![span(Span::Text("ツ".into()), Vec::new())]),
+                        vec![Modifier::Style(SpanStyle::Style("red".into()))],
+                    ),
+                ]),
+                vec![effect],
+            ),
+            TextSpanBundle::Effect(TypeWriterCommand::Pause(1.25)),
+            TextSpanBundle::Effect(TypeWriterCommand::WaitForInput),
+            TextSpanBundle::Effect(TypeWriterCommand::WaitFor {
+                signal: String::from("door_open"),
+                timeout: None,
+            }),
+            TextSpanBundle::Effect(TypeWriterCommand::WaitFor {
+                signal: String::from("door_open"),
+                timeout: Some(2.5),
+            }),
+            TextSpanBundle::Event(
+                TypeWriterEvent::new("my_event"),
+                TypeWriterEventArgs::default(),
+            ),
+            TextSpanBundle::Event(
+                TypeWriterEvent::new("shake_camera"),
+                TypeWriterEventArgs {
+                    args: vec!["0.5".into(), "a b".into()],
+                    named_args: vec![NamedArg::new("duration", "2")],
+                },
+            ),
+            TextSpanBundle::NamedCallback(TypeWriterNamedCallback::new("play_sound")),
+        ]);
+
+        let mut parsed = PrettyTextParser::spans(&spans.to_markup().unwrap()).unwrap();
+        strip_sources(&mut parsed.0);
+        assert_eq!(parsed, spans);
+    }
+
+    #[test]
+    fn generated_markup_round_trip() {
+        struct Rng(u64);
+
+        impl Rng {
+            fn next(&mut self, n: usize) -> usize {
+                // xorshift64
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                (self.0 % n as u64) as usize
+            }
+
+            fn string(&mut self, min: usize) -> String {
+                const CHARS: &[char] = &[
+                    'a', 'z', '0', ' ', '\n', 'ツ', '`', '!', '[', ']', '<', '>', '{', '}', '(',
+                    ')', ',', '"', '\\', '=',
+                ];
+                (0..min + self.next(6))
+                    .map(|_| CHARS[self.next(CHARS.len())])
+                    .collect()
+            }
+
+            // Mostly valid names, with the occasional name that can not be written.
+            fn name(&mut self) -> &'static str {
+                const VALID: &[&str] = &["a", "b_c", "ツ", "x.y"];
+                if self.next(16) == 0 {
+                    INVALID[self.next(INVALID.len())]
+                } else {
+                    VALID[self.next(VALID.len())]
+                }
+            }
+        }
+
+        // Names that are invalid in at least one position.
+        const INVALID: &[&str] = &["", "a b", "x]", "a|b", "{x}", "?q", "wait:x", "a:b"];
+
+        fn generate(rng: &mut Rng, depth: usize) -> Vec<TextSpanBundle> {
+            let mut bundles = Vec::new();
+            let mut text = false;
+            let mut content = false;
+            for _ in 0..1 + rng.next(4) {
+                // Adjacent text spans are merged by the parser.
+                let bundle = match rng.next(if depth > 0 { 9 } else { 8 }) {
+                    0 if !text => TextSpanBundle::Span {
+                        span: Span::Text(rng.string(1).into()),
+                        mods: Modifiers::default(),
+                        source: MarkupSource::default(),
+                    },
+                    1 => TextSpanBundle::Effect(TypeWriterCommand::Speed(
+                        [0.5, 1.0, 2.25][rng.next(3)],
+                    )),
+                    2 => TextSpanBundle::Effect(TypeWriterCommand::Pause(
+                        [0.0, 0.1, 3.0][rng.next(3)],
+                    )),
+                    3 => TextSpanBundle::Event(
+                        TypeWriterEvent::new(rng.name()),
+                        TypeWriterEventArgs::default(),
+                    ),
+                    4 => TextSpanBundle::Effect(TypeWriterCommand::WaitFor {
+                        signal: String::from(rng.name()),
+                        timeout: None,
+                    }),
+                    5 => TextSpanBundle::NamedCallback(TypeWriterNamedCallback::new(rng.name())),
+                    6 => TextSpanBundle::Span {
+                        span: if rng.next(2) == 0 {
+                            Span::Variable(rng.name().into())
+                        } else {
+                            Span::Image(rng.name().into())
+                        },
+                        mods: Modifiers::default(),
+                        source: MarkupSource::default(),
+                    },
+                    // Empty spans are omitted, so they do not separate text.
+                    7 => {
+                        bundles.push(TextSpanBundle::Span {
+                            span: Span::Bundles(Vec::new()),
+                            mods: Modifiers(vec![modifier(rng)]),
+                            source: MarkupSource::default(),
+                        });
+                        continue;
+                    }
+                    8 => TextSpanBundle::Span {
+                        span: Span::Bundles(generate(rng, depth - 1)),
+                        mods: Modifiers((0..1 + rng.next(3)).map(|_| modifier(rng)).collect()),
+                        source: MarkupSource::default(),
+                    },
+                    _ => continue,
+                };
+
+                text = matches!(
+                    bundle,
+                    TextSpanBundle::Span {
+                        span: Span::Text(_),
+                        ..
+                    }
+                );
+                content = true;
+                bundles.push(bundle);
+            }
+
+            if !content {
+                bundles.push(TextSpanBundle::Event(
+                    TypeWriterEvent::new("a"),
+                    TypeWriterEventArgs::default(),
+                ));
+            }
+            bundles
+        }
+
+        fn modifier(rng: &mut Rng) -> Modifier {
+            if rng.next(3) == 0 {
+                return Modifier::Style(SpanStyle::Style(rng.name().into()));
+            }
+
+            Modifier::Effect(PrettyTextEffect {
+                tag: rng.name().into(),
+                args: (0..rng.next(3)).map(|_| rng.string(0).into()).collect(),
+                named_args: (0..rng.next(3))
+                    .map(|i| NamedArg::new(["a", "b", "c"][i], rng.string(0)))
+                    .collect(),
+            })
+        }
+
+        fn omit_empty(bundles: &mut Vec<TextSpanBundle>) {
+            for bundle in bundles.iter_mut() {
+                if let TextSpanBundle::Span {
+                    span: Span::Bundles(bundles),
+                    ..
+                } = bundle
+                {
+                    omit_empty(bundles);
+                }
+            }
+            bundles.retain(|bundle| {
+                !matches!(bundle, TextSpanBundle::Span { span, mods, .. } if !mods.0.is_empty() && span.is_empty())
+            });
+        }
+
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        let mut invalid = 0;
+        for _ in 0..500 {
+            let mut spans = PrettyTextSpans(generate(&mut rng, 2));
+            let markup = match spans.to_markup() {
+                Ok(markup) => markup,
+                Err(InvalidMarkupName(name)) => {
+                    assert!(INVALID.contains(&name.as_str()), "{name}");
+                    invalid += 1;
+                    continue;
+                }
+            };
+
+            let mut parsed = PrettyTextParser::spans(&markup).unwrap_or_else(|e| panic!("{e}"));
+            strip_sources(&mut parsed.0);
+            omit_empty(&mut spans.0);
+            assert_eq!(parsed, spans, "{markup}");
+        }
+        assert!((1..250).contains(&invalid), "{invalid}");
+    }
+
+    #[test]
+    fn bbcode_syntax() {
+        for (bbcode, markup) in [
+            ("plain text", "plain text"),
+            ("[wave]effect[/wave]", "`effect`[wave]"),
+            ("[!red]style[/!red]", "`style`[!red]"),
+            (
+                "[ wave(1, max_height=2), !red ]mods[/ wave ]",
+                "`mods`[wave(1, max_height=2), !red]",
+            ),
+            (
+                "[wave]nested [!red]span[/] text[/wave]",
+                "`nested `span`[!red] text`[wave]",
+            ),
+            (
+                "<2>fast[wave]pause[1]{event}[/wave]",
+                "<2>fast`pause[1]{event}`[wave]",
+            ),
+            ("\\[wave\\] \\`escaped\\`", "\\[wave\\] \\`escaped\\`"),
+            ("a[wave][!red][/][/wave]b", "ab"),
+        ] {
+            let spans = PrettyTextParser::bbcode(bbcode).unwrap_or_else(|e| panic!("{e}"));
+            assert_eq!(spans.to_markup().unwrap(), markup);
+        }
+
+        let str = "a [wave]b[/wave]";
+        let spans = PrettyTextParser::bbcode(str).unwrap();
+        let TextSpanBundle::Span { source, .. } = &spans.0[1] else {
+            panic!("expected span");
+        };
+        assert_eq!(&str[source.range.clone()], "[wave]b[/wave]");
+    }
+
+    #[track_caller]
+    fn assert_bbcode_err(str: &str, kind: ParseErrorKind, span: Range<usize>) {
+        let err = PrettyTextParser::bbcode(str).unwrap_err();
+        assert_eq!(err.kind(), kind, "{err}");
+        assert_eq!(err.span(), span, "{err}");
+    }
+
+    #[test]
+    fn bbcode_errors() {
+        assert_bbcode_err("[wave]text", ParseErrorKind::UnclosedTag, 0..1);
+        assert_bbcode_err("text[/wave]", ParseErrorKind::UnexpectedCloseTag, 4..5);
+        assert_bbcode_err(
+            "[wave]text[/shake]",
+            ParseErrorKind::MismatchedCloseTag,
+            10..11,
+        );
+        assert_bbcode_err("[]text[/]", ParseErrorKind::EmptyModifiers, 1..2);
+        assert_bbcode_err(
+            "[wave(1 2)]text[/wave]",
+            ParseErrorKind::InvalidEffectArgs,
+            6..9,
+        );
+
+        let err = PrettyTextParser::bbcode("[wave][!red]text[/wave][/!red]").unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::MismatchedCloseTag);
+        assert_eq!(err.span(), 16..17);
+        assert_eq!(err.expected(), ["`[/!red]`"]);
+    }
+
+    #[track_caller]
+    fn assert_err_kind(str: &str, kind: ParseErrorKind, span: Range<usize>) {
+        let err = PrettyTextParser::spans(str).unwrap_err();
+        assert_eq!(err.kind(), kind, "{err}");
+        assert_eq!(err.span(), span, "{err}");
+        assert_eq!(err.token(), (!span.is_empty()).then(|| &str[span]), "{err}");
+    }
+
+    #[test]
+    fn parser_error_kinds() {
+        assert_err_kind("", ParseErrorKind::EmptyInput, 0..0);
+
+        assert_err_kind("`unclosed", ParseErrorKind::UnclosedSpan, 0..1);
+        assert_err_kind("unclosed`", ParseErrorKind::UnclosedSpan, 8..9);
+        assert_err_kind("`no mods` text", ParseErrorKind::MissingModifiers, 9..14);
+        assert_err_kind("`empty mods`[]", ParseErrorKind::EmptyModifiers, 13..14);
+        assert_err_kind("`unclosed`[wave", ParseErrorKind::UnclosedModifiers, 15..15);
+        assert_err_kind(
+            "`bad mod`[my wave]",
+            ParseErrorKind::InvalidModifier,
+            10..17,
+        );
+        assert_err_kind(
+            "`no comma`[wave(1 2)]",
+            ParseErrorKind::InvalidEffectArgs,
+            16..19,
+        );
+        assert_err_kind(
+            "`named`[wave(a=1, 2)]",
+            ParseErrorKind::InvalidEffectArgs,
+            17..19,
+        );
+        assert_err_kind(
+            "`unclosed`[wave(]",
+            ParseErrorKind::InvalidEffectArgs,
+            16..17,
+        );
+
+        assert_err_kind("text<fast>", ParseErrorKind::InvalidCommand, 5..9);
+        assert_err_kind("text[slow]", ParseErrorKind::InvalidCommand, 4..5);
+        assert_err_kind("unclosed{", ParseErrorKind::InvalidEvent, 9..9);
+        assert_err_kind("{`styled`[!red]}", ParseErrorKind::InvalidEvent, 1..2);
+
+        assert_err_kind("escaped \\n", ParseErrorKind::InvalidEscape, 8..9);
+        assert_err_kind(
+            r#"`bad escape`[sound("\q")]"#,
+            ParseErrorKind::InvalidEscape,
+            21..22,
+        );
+        assert_err_kind(
+            r#"`unclosed`[sound("door)]"#,
+            ParseErrorKind::InvalidEffectArgs,
+            24..24,
+        );
+        assert_err_kind("unclosed}", ParseErrorKind::UnmatchedDelimiter, 8..9);
+        assert_err_kind("{$my name}", ParseErrorKind::InvalidVariable, 1..9);
+        assert_err_kind("{$}", ParseErrorKind::InvalidVariable, 1..2);
+        assert_err_kind("{$name", ParseErrorKind::InvalidVariable, 6..6);
+        assert_err_kind("{img:}", ParseErrorKind::InvalidImage, 1..5);
+        assert_err_kind("{img:a b}", ParseErrorKind::InvalidImage, 1..8);
+        assert_err_kind("{wait:}", ParseErrorKind::InvalidCommand, 1..6);
+        assert_err_kind("{@}", ParseErrorKind::InvalidCallback, 1..2);
+        assert_err_kind("{@a b}", ParseErrorKind::InvalidCallback, 1..5);
+        assert_err_kind("{wait:door:soon}", ParseErrorKind::InvalidCommand, 1..15);
+        assert_err_kind("{wait:door:-1}", ParseErrorKind::InvalidCommand, 1..13);
+        assert_err_kind("{wait:door:NaN}", ParseErrorKind::InvalidCommand, 1..14);
+        assert_err_kind("{wait:door:inf}", ParseErrorKind::InvalidCommand, 1..14);
+        assert_err_kind("{?}", ParseErrorKind::InvalidCondition, 1..2);
+        assert_err_kind("{?key}", ParseErrorKind::InvalidCondition, 5..6);
+        assert_err_kind("{?key|a|b|c}", ParseErrorKind::InvalidCondition, 11..12);
+        assert_err_kind("{#count|a}", ParseErrorKind::InvalidCondition, 9..10);
+        assert_err_kind("{?key|a", ParseErrorKind::InvalidCondition, 7..7);
+    }
+
+    #[test]
+    fn parser_error_multibyte_span() {
+        let str = "ツ `ツ`[]";
+        let err = PrettyTextParser::spans(str).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::EmptyModifiers);
+        assert_eq!(&str[err.span()], "]");
+        assert_eq!(err.line_col(), (1, 7));
+
+        let str = "line one\n`ツ`[]";
+        let err = PrettyTextParser::spans(str).unwrap_err();
+        assert_eq!(err.line_col(), (2, 5));
+        assert!(err.to_string().contains("2 | `ツ`[]\n  |     ^"), "{err}");
+    }
 }
@@ -0,0 +1,399 @@
+//! Annotates text with small readings, such as Japanese furigana.
+//!
+//! The `ruby` effect lays out its text centred above the span it modifies.
+//!
+//! ```
+//! # use bevy::prelude::*;
+#![doc = include_str!("../docs/pretty.txt")]
+//! #
+//! # let mut world = World::new();
+//! world.spawn(pretty!("`漢字`[ruby(かんじ)]を`読`[ruby(よ)]む"));
+//!
+//! // Annotations are half the font size of their span by default.
+//! world.spawn(pretty!("`東京`[ruby(とうきょう, scale=0.4)]"));
+//! ```
+//!
+//! The annotation's [`Glyph`](crate::glyph::Glyph)s belong to the same
+//! [`Glyphs`](crate::glyph::Glyphs) root as the span, so they are revealed
+//! together with the span by a [`TypeWriter`](crate::type_writer::TypeWriter).
+//! Effects and materials of the span are also applied to its annotation.
+//!
+//! # ECS Structure
+//!
+//! The annotation is laid out by a hidden [`Text2d`] entity, which is a child
+//! of the text root and related to its span with [`RubyAnnotationOf`]. Its
+//! [`TextFont`] and [`TextColor`] are kept in sync with the span.
+//!
+//! Spans that wrap across lines are annotated on their first line.
+
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+use bevy::text::{PositionedGlyph, TextLayoutInfo, Update2dText};
+
+use crate::PrettyText;
+use crate::dynamic_effects::{DynamicEffect, NamedArg, PrettyTextEffectAppExt, field_args};
+use crate::glyph::GlyphSystems;
+use crate::parser::{Modifier, Modifiers};
+
+/// Registers the `ruby` effect and lays out annotations.
+#[derive(Debug)]
+pub struct RubyPlugin;
+
+impl Plugin for RubyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_pretty_effect::<Ruby>(Ruby::TAG).add_systems(
+            PostUpdate,
+            (
+                sync_annotations.before(Update2dText),
+                annotation_layout_changed
+                    .after(Update2dText)
+                    .before(GlyphSystems::Construct),
+            ),
+        );
+
+        app.register_type::<Ruby>()
+            .register_type::<RubyAnnotation>()
+            .register_type::<RubyAnnotationOf>();
+    }
+}
+
+/// Annotates a span with `text`, e.g. ``"`漢字`[ruby(かんじ)]"``.
+///
+/// See [`ruby`](crate::ruby).
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[require(PrettyText)]
+pub struct Ruby {
+    /// The annotation.
+    pub text: String,
+
+    /// Font size of the annotation relative to the span.
+    pub scale: f32,
+}
+
+impl Ruby {
+    /// The tag of the `ruby` effect.
+    pub const TAG: &'static str = "ruby";
+}
+
+impl Default for Ruby {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            scale: 0.5,
+        }
+    }
+}
+
+impl DynamicEffect for Ruby {
+    fn insert_from_args(
+        &self,
+        args: &[Cow<'static, str>],
+        entity: &mut EntityCommands,
+    ) -> Result<()> {
+        self.insert_from_named_args(args, &[], entity)
+    }
+
+    fn insert_from_named_args(
+        &self,
+        args: &[Cow<'static, str>],
+        named_args: &[NamedArg],
+        entity: &mut EntityCommands,
+    ) -> Result<()> {
+        let mut ruby = Self::default();
+        for (field, value) in field_args(Self::TAG, self.fields().unwrap(), args, named_args)? {
+            match field {
+                "text" => ruby.text = value.to_string(),
+                "scale" => {
+                    ruby.scale = value
+                        .parse()
+                        .map_err(|err| format!("failed to parse field `scale` in `ruby`: {err}"))?;
+                }
+                _ => unreachable!("unknown fields are rejected by `field_args`"),
+            }
+        }
+
+        entity.insert(ruby);
+        Ok(())
+    }
+
+    fn fields(&self) -> Option<&'static [&'static str]> {
+        Some(&["text", "scale"])
+    }
+}
+
+/// Stores the annotation entity of a [`Ruby`] span.
+#[derive(Debug, Component, Reflect)]
+#[relationship_target(relationship = RubyAnnotationOf, linked_spawn)]
+pub struct RubyAnnotation(Entity);
+
+impl RubyAnnotation {
+    /// The annotation entity.
+    pub fn get(&self) -> Entity {
+        self.0
+    }
+}
+
+/// Stores the [`Ruby`] span of an annotation entity.
+///
+/// See [`ruby`](crate::ruby#ecs-structure).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Reflect)]
+#[relationship(relationship_target = RubyAnnotation)]
+pub struct RubyAnnotationOf(Entity);
+
+impl RubyAnnotationOf {
+    /// The annotated span.
+    pub fn span(&self) -> Entity {
+        self.0
+    }
+}
+
+fn sync_annotations(
+    mut commands: Commands,
+    spans: Query<
+        (
+            Entity,
+            Ref<Ruby>,
+            &TextFont,
+            &TextColor,
+            Option<&Modifiers>,
+            Option<&RubyAnnotation>,
+        ),
+        Without<RubyAnnotationOf>,
+    >,
+    mut annotations: Query<(&mut Text2d, &mut TextFont, &mut TextColor), With<RubyAnnotationOf>>,
+    parents: Query<&ChildOf>,
+    roots: Query<(), With<Text2d>>,
+) {
+    for (entity, ruby, font, color, mods, annotation) in spans.iter() {
+        let annotation_font = TextFont {
+            font_size: font.font_size * ruby.scale,
+            ..font.clone()
+        };
+
+        let Some(annotation) = annotation else {
+            let Some(root) = parents
+                .iter_ancestors(entity)
+                .find(|ancestor| roots.contains(*ancestor))
+            else {
+                continue;
+            };
+
+            // The annotation shares the effects of its span, but not its styles.
+            let mods: Vec<_> = mods
+                .map(|mods| {
+                    mods.0
+                        .iter()
+                        .filter(
+                            |m| matches!(m, Modifier::Effect(effect) if effect.tag != Ruby::TAG),
+                        )
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let mut annotation = commands.spawn((
+                PrettyText,
+                RubyAnnotationOf(entity),
+                ChildOf(root),
+                Text2d::new(ruby.text.clone()),
+                annotation_font,
+                *color,
+                TextLayout::new_with_no_wrap(),
+            ));
+            if !mods.is_empty() {
+                annotation.insert(Modifiers(mods));
+            }
+            continue;
+        };
+
+        let Ok((mut text, mut text_font, mut text_color)) = annotations.get_mut(annotation.get())
+        else {
+            continue;
+        };
+
+        if ruby.is_changed() && text.0 != ruby.text {
+            text.0.clone_from(&ruby.text);
+        }
+        if text_font.font != annotation_font.font
+            || text_font.font_size != annotation_font.font_size
+            || text_font.font_smoothing != annotation_font.font_smoothing
+        {
+            *text_font = annotation_font;
+        }
+        if *text_color != *color {
+            *text_color = *color;
+        }
+    }
+}
+
+// Annotation glyphs are constructed with the glyphs of their text root.
+fn annotation_layout_changed(
+    annotations: Query<&ChildOf, (With<RubyAnnotationOf>, Changed<TextLayoutInfo>)>,
+    mut roots: Query<&mut TextLayoutInfo, Without<RubyAnnotationOf>>,
+) {
+    for child_of in annotations.iter() {
+        if let Ok(mut layout) = roots.get_mut(child_of.parent()) {
+            layout.set_changed();
+        }
+    }
+}
+
+/// Positions `annotation` centred above the `base` glyphs of its span.
+///
+/// Each annotation glyph takes the indices of a base glyph, evenly spread
+/// across the span, so that it is revealed with the base glyph.
+pub(crate) fn layout_annotation(
+    base: &[PositionedGlyph],
+    annotation: &TextLayoutInfo,
+) -> Vec<PositionedGlyph> {
+    let Some(first) = base.first() else {
+        return Vec::new();
+    };
+
+    let base = base
+        .iter()
+        .filter(|glyph| glyph.line_index == first.line_index)
+        .collect::<Vec<_>>();
+    let (left, right, top) = base.iter().fold(
+        (f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        |(left, right, top), glyph| {
+            let min = glyph.position - glyph.size / 2.;
+            let max = glyph.position + glyph.size / 2.;
+            (left.min(min.x), right.max(max.x), top.max(max.y))
+        },
+    );
+
+    let offset = Vec2::new((left + right - annotation.size.x) / 2., top);
+    let len = annotation.glyphs.len();
+    annotation
+        .glyphs
+        .iter()
+        .enumerate()
+        .map(|(i, glyph)| {
+            let base = base[i * base.len() / len];
+            PositionedGlyph {
+                position: glyph.position + offset,
+                span_index: base.span_index,
+                line_index: base.line_index,
+                byte_index: base.byte_index,
+                byte_length: base.byte_length,
+                ..glyph.clone()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+    use bevy::text::{GlyphAtlasInfo, GlyphAtlasLocation, PositionedGlyph, TextLayoutInfo};
+
+    use crate::glyph::{Glyph, GlyphSpanEntity};
+    use crate::parser::PrettyTextParser;
+    use crate::test::prepare_app;
+    use crate::type_writer::Reveal;
+
+    use super::{RubyAnnotation, layout_annotation};
+
+    fn glyph(x: f32, y: f32, size: f32, byte_index: usize) -> PositionedGlyph {
+        PositionedGlyph {
+            position: Vec2::new(x, y),
+            size: Vec2::splat(size),
+            atlas_info: GlyphAtlasInfo {
+                texture: Handle::default(),
+                texture_atlas: Handle::default(),
+                location: GlyphAtlasLocation {
+                    glyph_index: 0,
+                    offset: IVec2::ZERO,
+                },
+            },
+            span_index: 1,
+            line_index: 0,
+            byte_index,
+            byte_length: 1,
+        }
+    }
+
+    #[test]
+    fn annotation_position() {
+        let base = [glyph(30., 10., 20., 4), glyph(50., 10., 20., 5)];
+        let annotation = TextLayoutInfo {
+            glyphs: vec![
+                glyph(5., 8., 10., 0),
+                glyph(15., 8., 10., 1),
+                glyph(25., 8., 10., 2),
+            ],
+            size: Vec2::new(30., 16.),
+        };
+
+        let glyphs = layout_annotation(&base, &annotation);
+        // Centred over 20..60, resting on top of the base glyphs.
+        let positions = glyphs.iter().map(|g| g.position).collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            vec![
+                Vec2::new(30., 28.),
+                Vec2::new(40., 28.),
+                Vec2::new(50., 28.)
+            ]
+        );
+
+        let indices = glyphs.iter().map(|g| g.byte_index).collect::<Vec<_>>();
+        assert_eq!(indices, vec![4, 4, 5]);
+        assert!(glyphs.iter().all(|g| g.span_index == 1));
+    }
+
+    #[test]
+    fn annotation_glyphs() {
+        let mut app = prepare_app();
+        app.update();
+
+        let root = app
+            .world_mut()
+            .spawn((
+                Reveal(3),
+                PrettyTextParser::bundle("a `bc`[ruby(xyz)]").unwrap(),
+            ))
+            .id();
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let mut spans = app.world_mut().query::<(Entity, &RubyAnnotation)>();
+        let (span, annotation) = spans.single(app.world()).unwrap();
+        let annotation = annotation.get();
+
+        let mut glyphs = app
+            .world_mut()
+            .query::<(&Glyph, &GlyphSpanEntity, &Visibility)>();
+        let glyphs = glyphs.iter(app.world()).collect::<Vec<_>>();
+        assert_eq!(glyphs.len(), 7);
+
+        let base_top = glyphs
+            .iter()
+            .filter(|(_, span_entity, _)| span_entity.0 == span)
+            .map(|(glyph, ..)| glyph.0.position.y)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let annotation_glyphs = glyphs
+            .iter()
+            .filter(|(_, span_entity, _)| span_entity.0 == annotation)
+            .collect::<Vec<_>>();
+        assert_eq!(annotation_glyphs.len(), 3);
+        assert!(
+            annotation_glyphs
+                .iter()
+                .all(|(glyph, ..)| glyph.0.position.y > base_top)
+        );
+
+        // "a b" is revealed, so the annotation glyphs over "b" are visible.
+        let visible = annotation_glyphs
+            .iter()
+            .filter(|(.., vis)| **vis == Visibility::Inherited)
+            .count();
+        assert_eq!(visible, 2);
+
+        app.world_mut().entity_mut(root).despawn();
+        assert!(app.world().get_entity(annotation).is_err());
+    }
+}
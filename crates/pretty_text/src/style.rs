@@ -204,9 +204,17 @@ impl quote::ToTokens for SpanStyle {
 
 /// Caches style entities.
 ///
-/// The registry is synced with styles in the ECS.
-#[derive(Debug, Default, Deref, DerefMut, Resource)]
-struct PrettyStyleRegistry(HashMap<&'static str, Entity>);
+/// The registry is synced with [`PrettyStyle`] entities in the ECS.
+#[derive(Debug, Default, Resource)]
+pub struct PrettyStyleRegistry(HashMap<&'static str, Entity>);
+
+impl PrettyStyleRegistry {
+    /// Retrieves the style entity registered with `tag`.
+    #[inline]
+    pub fn get(&self, tag: &str) -> Option<Entity> {
+        self.0.get(tag).copied()
+    }
+}
 
 fn register(mut world: DeferredWorld, ctx: HookContext) {
     let tag = world.get::<PrettyStyle>(ctx.entity).unwrap().0;
@@ -216,12 +224,12 @@ fn register(mut world: DeferredWorld, ctx: HookContext) {
         error!("style `{}` is already registered", tag);
     }
 
-    registry.insert(tag, ctx.entity);
+    registry.0.insert(tag, ctx.entity);
 }
 
 fn unregister(mut world: DeferredWorld, ctx: HookContext) {
     let tag = world.get::<PrettyStyle>(ctx.entity).unwrap().0;
-    world.resource_mut::<PrettyStyleRegistry>().remove(tag);
+    world.resource_mut::<PrettyStyleRegistry>().0.remove(tag);
 }
 
 fn apply_span_style(
@@ -0,0 +1,245 @@
+//! Dynamic events are normal rust [`Event`]s that are constructed from the
+//! arguments of a [`TypeWriterEvent`] and triggered by a
+//! [`TypeWriter`](super::TypeWriter).
+//!
+//! # Using Dynamic Events
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::type_writer::*;
+//! # use pretty_text::type_writer::dynamic_events::*;
+#![doc = include_str!("../../docs/pretty.txt")]
+//! #
+//! #[derive(Default, Event)]
+//! struct ShakeCamera {
+//!     intensity: f32,
+//!     duration: f32,
+//! }
+//!
+//! # let mut world = World::new();
+//! // Registered with `app.register_pretty_event::<ShakeCamera>("shake_camera")`.
+//! world.spawn((
+//!     TypeWriter::new(30.0),
+//!     pretty!("The ground {shake_camera(0.5, 2)}rumbles."),
+//! ));
+//!
+//! // `ShakeCamera` is triggered on the type writer entity.
+//! fn shake_camera(trigger: Trigger<ShakeCamera>) {
+//!     println!("{} for {}s", trigger.intensity, trigger.duration);
+//! }
+//! ```
+//!
+//! Arguments follow the rules of [effect arguments](crate::dynamic_effects):
+//! missing arguments are defaulted, and named arguments are supported,
+//! e.g. `{shake_camera(duration=2)}`.
+//!
+//! A [`TypeWriterEvent`] is triggered and emitted for every event tag, whether
+//! or not a dynamic event is registered with the tag.
+//!
+//! # Defining Custom Events
+//!
+//! Dynamic events are registered with a tag using
+//! [`PrettyTextEventAppExt::register_pretty_event`].
+//!
+//! ```ignore
+//! #[derive(Default, Event, DynamicEvent)]
+//! struct ShakeCamera {
+//!     intensity: f32,
+//!     duration: f32,
+//! }
+//!
+//! app.register_pretty_event::<ShakeCamera>("shake_camera");
+//! ```
+
+use std::borrow::Cow;
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use super::hierarchy::{TypeWriterEvent, TypeWriterEventArgs};
+use crate::dynamic_effects::NamedArg;
+
+/// Extension trait for registering [dynamic events](crate::type_writer::dynamic_events).
+pub trait PrettyTextEventAppExt {
+    /// Register event `T` with a `tag`.
+    fn register_pretty_event<T: Default + DynamicEvent>(&mut self, tag: &'static str) -> &mut Self;
+}
+
+impl PrettyTextEventAppExt for App {
+    fn register_pretty_event<T: Default + DynamicEvent>(&mut self, tag: &'static str) -> &mut Self {
+        self.add_systems(PreStartup, move |mut registry: ResMut<DynEventRegistry>| {
+            registry.register(tag, T::default());
+        })
+    }
+}
+
+/// Constructs `Self` from `args` and triggers it on an entity.
+///
+/// See [`dynamic_events`](crate::type_writer::dynamic_events).
+///
+/// This trait should be derived with
+/// [`DynamicEvent`](https://docs.rs/bevy_pretty_text/derive.DynamicEvent.html).
+pub trait DynamicEvent: Send + Sync + 'static {
+    /// Construct a dynamic event from `args` and trigger it on `entity`.
+    ///
+    /// Returns a [`BevyError`] if the event can not constructed from `args`.
+    fn trigger_from_args(
+        &self,
+        args: &[Cow<'static, str>],
+        entity: &mut EntityCommands,
+    ) -> Result<()>;
+
+    /// Construct a dynamic event from positional `args` followed by `named_args`
+    /// and trigger it on `entity`.
+    ///
+    /// The default implementation does not support named arguments and
+    /// forwards `args` to [`DynamicEvent::trigger_from_args`].
+    ///
+    /// Returns a [`BevyError`] if the event can not constructed from the arguments.
+    fn trigger_from_named_args(
+        &self,
+        args: &[Cow<'static, str>],
+        named_args: &[NamedArg],
+        entity: &mut EntityCommands,
+    ) -> Result<()> {
+        if let Some(arg) = named_args.first() {
+            return Err(
+                format!("event does not support named arguments, got `{}`", arg.name).into(),
+            );
+        }
+
+        self.trigger_from_args(args, entity)
+    }
+}
+
+/// Dynamic event registry.
+///
+/// See [`dynamic_events`](crate::type_writer::dynamic_events).
+#[derive(Default, Resource)]
+pub struct DynEventRegistry(HashMap<&'static str, Box<dyn DynamicEvent>>);
+
+impl std::fmt::Debug for DynEventRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("DynEventRegistry")
+            .field(&self.0.keys())
+            .finish()
+    }
+}
+
+impl DynEventRegistry {
+    /// Register an `event` with `tag`.
+    #[inline]
+    pub fn register(&mut self, tag: &'static str, event: impl DynamicEvent) {
+        if self.0.get(tag).is_some() {
+            error!("event `{tag}` is already registered");
+        }
+
+        self.0.insert(tag, Box::new(event));
+    }
+
+    /// Unregisters the event with `tag`.
+    #[inline]
+    pub fn unregister(&mut self, tag: &'static str) {
+        self.0.remove(tag);
+    }
+
+    /// Retrieves the event registered with `tag`.
+    #[inline]
+    pub fn get(&self, tag: &str) -> Option<&dyn DynamicEvent> {
+        self.0.get(tag).map(|event| event.as_ref())
+    }
+
+    // Triggers the dynamic event registered with the tag of `event` on `entity`.
+    pub(super) fn trigger(
+        &self,
+        event: &TypeWriterEvent,
+        args: Option<&TypeWriterEventArgs>,
+        entity: &mut EntityCommands,
+    ) {
+        let Some(handler) = self.get(&event.0) else {
+            if args.is_some() {
+                error!("event `{}` is not registered", event.0);
+            }
+            return;
+        };
+
+        let (args, named_args) = args
+            .map(|args| (args.args.as_slice(), args.named_args.as_slice()))
+            .unwrap_or_default();
+        if let Err(err) = handler.trigger_from_named_args(args, named_args, entity) {
+            error!("failed to trigger event `{}`: {err}", event.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+    use bevy::time::TimeUpdateStrategy;
+
+    use pretty_text_macros::DynamicEvent;
+
+    use crate::parser::PrettyTextParser;
+    use crate::test::prepare_app;
+    use crate::type_writer::TypeWriter;
+
+    use super::PrettyTextEventAppExt;
+
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Event, DynamicEvent)]
+    struct Shake {
+        intensity: f32,
+        duration: f32,
+    }
+
+    fn shake(intensity: f32, duration: f32) -> Shake {
+        Shake {
+            intensity,
+            duration,
+        }
+    }
+
+    #[derive(Default, Resource)]
+    struct Shakes(Vec<(Entity, Shake)>);
+
+    #[test]
+    fn trigger_event() {
+        let mut app = prepare_app();
+        app.register_pretty_event::<Shake>("shake")
+            .init_resource::<Shakes>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+        app.update();
+
+        let entity = app
+            .world_mut()
+            .spawn((
+                TypeWriter::new(40.),
+                PrettyTextParser::bundle(concat!(
+                    "a{shake}b{shake(0.5, 2)}c{shake(duration=3)}{shake(1, duration=3)}",
+                    // Not triggered: `intensity` is supplied twice and `unknown` is not registered.
+                    "{shake(1, intensity=2)}{unknown(1)}",
+                ))
+                .unwrap(),
+            ))
+            .observe(|trigger: Trigger<Shake>, mut shakes: ResMut<Shakes>| {
+                shakes.0.push((trigger.target(), *trigger.event()));
+            })
+            .id();
+        for _ in 0..3 {
+            app.update();
+        }
+
+        assert_eq!(
+            app.world().resource::<Shakes>().0,
+            [
+                (entity, shake(0., 0.)),
+                (entity, shake(0.5, 2.)),
+                (entity, shake(0., 3.)),
+                (entity, shake(1., 3.)),
+            ]
+        );
+    }
+}
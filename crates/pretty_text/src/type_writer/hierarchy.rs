@@ -10,13 +10,18 @@
 //! sequencing components. In addition to Bevy's [`TextSpan`], this module provides
 //! three additional sequencing components:
 //! - [`TypeWriterCommand`]
-//! - [`TypeWriterEvent`]
-//! - [`TypeWriterCallback`]
+//! - [`TypeWriterEvent`], with optional [`TypeWriterEventArgs`]
+//! - [`TypeWriterCallback`], or a [`TypeWriterNamedCallback`]
 
+use std::borrow::Cow;
 use std::sync::Arc;
 
+use bevy::ecs::system::SystemId;
+use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 
+use crate::dynamic_effects::NamedArg;
+
 /// A command processed by [`TypeWriter`](super::TypeWriter).
 ///
 /// ```
@@ -43,7 +48,7 @@ use bevy::prelude::*;
 ///     ]
 /// ));
 /// ```
-#[derive(Debug, Clone, Copy, Component, Reflect)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 pub enum TypeWriterCommand {
@@ -52,6 +57,23 @@ pub enum TypeWriterCommand {
 
     /// Pause for a duration in seconds.
     Pause(f32),
+
+    /// Wait until [`TypeWriter::advance`](super::TypeWriter::advance) is called.
+    ///
+    /// See [`TypeWriterWaiting`](super::TypeWriterWaiting).
+    WaitForInput,
+
+    /// Wait until a [`TypeWriterSignal`](super::TypeWriterSignal) with a matching
+    /// tag is triggered.
+    ///
+    /// See [`WaitingForSignal`](super::WaitingForSignal).
+    WaitFor {
+        /// The tag of the signal.
+        signal: String,
+
+        /// Continue after a duration in seconds if the signal is not triggered.
+        timeout: Option<f32>,
+    },
 }
 
 /// An event emitted by [`TypeWriter`](super::TypeWriter).
@@ -87,7 +109,7 @@ pub enum TypeWriterCommand {
 ///         assert_eq!(trigger.0, "my_event");
 ///     });
 /// ```
-#[derive(Debug, Clone, Component, Event, Deref, Reflect)]
+#[derive(Debug, Clone, PartialEq, Eq, Component, Event, Deref, Reflect)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 pub struct TypeWriterEvent(pub String);
@@ -106,6 +128,64 @@ impl TypeWriterEvent {
     }
 }
 
+/// Arguments of a [`TypeWriterEvent`], e.g. `{shake_camera(0.5, 2)}`.
+///
+/// The arguments construct the [dynamic event](super::dynamic_events) registered
+/// with the tag of the [`TypeWriterEvent`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use pretty_text::type_writer::*;
+/// # use pretty_text::type_writer::hierarchy::*;
+#[doc = include_str!("../../docs/pretty.txt")]
+/// #
+/// # let mut world = World::new();
+/// world.spawn((
+///     TypeWriter::new(30.0),
+///     pretty!("{shake_camera(0.5, 2)}Rumble!"),
+/// ));
+///
+/// // The `pretty` invocation above will expand to:
+/// world.spawn((
+///     TypeWriter::new(30.0),
+///     Text2d::default(),
+///     children![
+///         (
+///             TypeWriterEvent::new("shake_camera"),
+///             TypeWriterEventArgs::new(["0.5", "2"]),
+///         ),
+///         TextSpan::new("Rumble!"),
+///     ],
+/// ));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+pub struct TypeWriterEventArgs {
+    /// Positional field arguments for a dynamic event.
+    pub args: Vec<Cow<'static, str>>,
+
+    /// Named field arguments for a dynamic event, following the positional `args`.
+    pub named_args: Vec<NamedArg>,
+}
+
+impl TypeWriterEventArgs {
+    /// Creates new positional event arguments.
+    #[inline]
+    pub fn new(args: impl IntoIterator<Item = impl Into<Cow<'static, str>>>) -> Self {
+        Self {
+            args: args.into_iter().map(Into::into).collect(),
+            named_args: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if there are no arguments.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty() && self.named_args.is_empty()
+    }
+}
+
 /// A [one shot system] triggered by [`TypeWriter`](super::TypeWriter).
 ///
 /// [one shot system]: https://github.com/bevyengine/bevy/blob/2bddbdfd7c920d1ea61245dcdb7ff1c155e6b03b/examples/ecs/one_shot_systems.rs
@@ -156,6 +236,13 @@ impl core::fmt::Debug for TypeWriterCallback {
     }
 }
 
+// Callbacks are only equal to their clones.
+impl PartialEq for TypeWriterCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
 // Implements default for skipping in `PrettyTextSpans`.
 impl Default for TypeWriterCallback {
     fn default() -> Self {
@@ -200,6 +287,119 @@ where
     }
 }
 
+/// A [one shot system] registered by name in the [`PrettyCallbackRegistry`] and run by
+/// [`TypeWriter`](super::TypeWriter).
+///
+/// Unlike [`TypeWriterCallback`], named callbacks can be parsed at run time and serialized.
+///
+/// [one shot system]: https://github.com/bevyengine/bevy/blob/2bddbdfd7c920d1ea61245dcdb7ff1c155e6b03b/examples/ecs/one_shot_systems.rs
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use pretty_text::type_writer::*;
+/// # use pretty_text::type_writer::hierarchy::*;
+/// #
+#[doc = include_str!("../../docs/pretty.txt")]
+#[doc = include_str!("../../docs/audio_player.txt")]
+/// #
+/// # let mut app = App::new();
+/// // Registering `play_fall_sound`.
+/// app.register_pretty_callback("play_fall_sound", play_fall_sound);
+///
+/// # let mut world = World::new();
+/// // Basic usage.
+/// world.spawn((
+///     TypeWriter::new(30.0),
+///     pretty!("The vase {@play_fall_sound}falls."),
+/// ));
+///
+/// // The `pretty` invocation above will expand to:
+/// world.spawn((
+///     TypeWriter::new(30.0),
+///     Text2d::default(),
+///     children![
+///         TextSpan::new("The vase "),
+///         TypeWriterNamedCallback::new("play_fall_sound"),
+///         TextSpan::new("falls."),
+///     ],
+/// ));
+///
+/// fn play_fall_sound(mut commands: Commands, server: Res<AssetServer>) {
+///     commands.spawn(AudioPlayer::new(
+///         server.load("fall.ogg"),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Component, Deref, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+pub struct TypeWriterNamedCallback(pub String);
+
+impl TypeWriterNamedCallback {
+    /// Creates a new named callback.
+    #[inline]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+}
+
+/// Extension trait for registering [named callbacks](TypeWriterNamedCallback).
+pub trait PrettyCallbackAppExt {
+    /// Register `system` as a callback with `name`.
+    fn register_pretty_callback<M>(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        system: impl IntoSystem<(), (), M> + 'static,
+    ) -> &mut Self;
+}
+
+impl PrettyCallbackAppExt for App {
+    fn register_pretty_callback<M>(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        system: impl IntoSystem<(), (), M> + 'static,
+    ) -> &mut Self {
+        let system = self.register_system(system);
+        self.world_mut()
+            .get_resource_or_init::<PrettyCallbackRegistry>()
+            .register(name, system);
+        self
+    }
+}
+
+/// Named callback registry.
+///
+/// See [`TypeWriterNamedCallback`].
+#[derive(Debug, Default, Resource)]
+pub struct PrettyCallbackRegistry(HashMap<Cow<'static, str>, SystemId>);
+
+impl PrettyCallbackRegistry {
+    /// Register a `system` with `name`.
+    #[inline]
+    pub fn register(&mut self, name: impl Into<Cow<'static, str>>, system: SystemId) {
+        let name = name.into();
+        if self.0.contains_key(&name) {
+            error!("callback `{name}` is already registered");
+        }
+
+        self.0.insert(name, system);
+    }
+
+    /// Unregisters the callback with `name`.
+    ///
+    /// The system is not removed from the [`World`].
+    #[inline]
+    pub fn unregister(&mut self, name: &str) -> Option<SystemId> {
+        self.0.remove(name)
+    }
+
+    /// Retrieves the system registered with `name`.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<SystemId> {
+        self.0.get(name).copied()
+    }
+}
+
 #[cfg(feature = "proc-macro")]
 impl quote::ToTokens for TypeWriterCommand {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
@@ -212,6 +412,21 @@ impl quote::ToTokens for TypeWriterCommand {
             Self::Pause(duration) => {
                 quote::quote! { bevy_pretty_text::type_writer::hierarchy::TypeWriterCommand::Pause(#duration) }
             }
+            Self::WaitForInput => {
+                quote::quote! { bevy_pretty_text::type_writer::hierarchy::TypeWriterCommand::WaitForInput }
+            }
+            Self::WaitFor { signal, timeout } => {
+                let timeout = match timeout {
+                    Some(timeout) => quote::quote! { Some(#timeout) },
+                    None => quote::quote! { None },
+                };
+                quote::quote! {
+                    bevy_pretty_text::type_writer::hierarchy::TypeWriterCommand::WaitFor {
+                        signal: String::from(#signal),
+                        timeout: #timeout,
+                    }
+                }
+            }
         });
     }
 }
@@ -229,3 +444,33 @@ impl quote::ToTokens for TypeWriterEvent {
         });
     }
 }
+
+#[cfg(feature = "proc-macro")]
+impl quote::ToTokens for TypeWriterEventArgs {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        use quote::TokenStreamExt;
+
+        let args = &self.args;
+        let named_args = &self.named_args;
+        tokens.append_all(quote::quote! {
+            bevy_pretty_text::type_writer::hierarchy::TypeWriterEventArgs {
+                args: vec![#(std::borrow::Cow::Borrowed(#args),)*],
+                named_args: vec![#(#named_args,)*],
+            }
+        });
+    }
+}
+
+#[cfg(feature = "proc-macro")]
+impl quote::ToTokens for TypeWriterNamedCallback {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        use quote::TokenStreamExt;
+
+        let name = &self.0;
+        tokens.append_all(quote::quote! {
+            bevy_pretty_text::type_writer::hierarchy::TypeWriterNamedCallback(
+                String::from(#name),
+            )
+        });
+    }
+}
@@ -0,0 +1,286 @@
+//! Humanised typing with uneven speed, typos and backspaces.
+//!
+//! A [`HumanTyping`] on a [`TypeWriter`](super::TypeWriter) entity varies the
+//! delay between units, and occasionally mistypes a neighbouring key before
+//! erasing it and typing the correct glyph.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::type_writer::*;
+//! # use pretty_text::type_writer::human::*;
+//! #
+//! # let mut world = World::new();
+//! world
+//!     .spawn((
+//!         TypeWriter::new(20.0),
+//!         HumanTyping::new(42)
+//!             .with_jitter(0.4)
+//!             .with_typos(0.05, 0.25),
+//!         Text2d::new("$ ssh root@mainframe"),
+//!     ))
+//!     .observe(|trigger: Trigger<GlyphErased>| {
+//!         println!("backspace over `{}`", trigger.text);
+//!     });
+//! ```
+//!
+//! A typo is revealed with [`GlyphRevealed`](super::GlyphRevealed) and erased
+//! with [`GlyphErased`](super::GlyphErased), decreasing the
+//! [`Reveal`](super::Reveal) of the type writer. Typos are only made in
+//! [`TypeWriterMode::Glyph`](super::TypeWriterMode::Glyph), and only with
+//! letters and digits that appear elsewhere in the text with the same font.
+//!
+//! Typing is deterministic for a given seed, text and [`TypeWriterMode`](super::TypeWriterMode).
+//!
+//! The delays of [`HumanTyping`] are not included in a
+//! [`TypeWriterTimeline`](super::timeline::TypeWriterTimeline).
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::text::{ComputedTextBlock, PositionedGlyph};
+
+use crate::glyph::{Glyph, GlyphSpanEntity, Glyphs};
+
+/// Types like a person, with uneven speed and typos.
+///
+/// See [`human`](crate::type_writer::human).
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct HumanTyping {
+    /// The fraction by which the delay between units varies, e.g. `0.3` varies
+    /// the delay between 70% and 130%.
+    pub jitter: f32,
+
+    /// The chance, from 0 to 1, that a glyph is mistyped.
+    pub typo_chance: f32,
+
+    /// The time in seconds that a typo is visible before it is erased.
+    pub typo_pause: f32,
+
+    state: u64,
+    corrected: Option<usize>,
+}
+
+impl Default for HumanTyping {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl HumanTyping {
+    /// Creates a new `HumanTyping` with `seed`.
+    #[inline]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            jitter: 0.3,
+            typo_chance: 0.03,
+            typo_pause: 0.3,
+            state: seed,
+            corrected: None,
+        }
+    }
+
+    /// Varies the delay between units by `jitter`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `jitter` is negative or not finite.
+    #[inline]
+    pub fn with_jitter(mut self, jitter: f32) -> Self {
+        assert!(
+            jitter.is_finite() && jitter >= 0.,
+            "invalid jitter: {jitter}"
+        );
+        self.jitter = jitter;
+        self
+    }
+
+    /// Mistypes glyphs with `chance`, showing each typo for `pause` seconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chance` is not within `0.0..=1.0`, or if `pause` is negative or
+    /// not finite.
+    #[inline]
+    pub fn with_typos(mut self, chance: f32, pause: f32) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&chance),
+            "invalid typo chance: {chance}"
+        );
+        assert!(
+            pause.is_finite() && pause >= 0.,
+            "invalid typo pause: {pause}"
+        );
+        self.typo_chance = chance;
+        self.typo_pause = pause;
+        self
+    }
+
+    // SplitMix64, which is stable across platforms and dependency versions.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // A random number in `0.0..1.0`.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    // The delay in seconds added to `interval`, the time between two units.
+    pub(super) fn jitter(&mut self, interval: f32) -> f32 {
+        interval * non_negative(self.jitter) * (2. * self.next_f32() - 1.)
+    }
+
+    // The time in seconds that a typo is visible.
+    pub(super) fn typo_delay(&self) -> f32 {
+        non_negative(self.typo_pause)
+    }
+}
+
+// The fields of `HumanTyping` can be set without the builder methods, so they are
+// checked where they are used. Invalid values disable the behaviour.
+fn non_negative(value: f32) -> f32 {
+    if value.is_finite() { value.max(0.) } else { 0. }
+}
+
+// A typo displayed by the type writer entity.
+#[derive(Component)]
+pub(super) struct Mistyped {
+    pub glyph: Entity,
+    pub text: String,
+    // The [`Glyph`] of the correct grapheme.
+    pub original: PositionedGlyph,
+    // The [`Reveal`](super::Reveal) after the typo.
+    pub reveal: usize,
+}
+
+// Restores the correct glyph once a typo is erased.
+pub(super) fn restore_mistyped(
+    trigger: Trigger<OnRemove, Mistyped>,
+    mut commands: Commands,
+    mistyped: Query<&Mistyped>,
+) {
+    if let Ok(mistyped) = mistyped.get(trigger.target()) {
+        commands
+            .entity(mistyped.glyph)
+            .try_insert(Glyph(mistyped.original.clone()));
+    }
+}
+
+// Finds typos in the glyphs of a text hierarchy.
+#[derive(SystemParam)]
+pub(super) struct Typist<'w, 's> {
+    glyphs: Query<'w, 's, (&'static Glyph, &'static GlyphSpanEntity)>,
+    fonts: Query<'w, 's, &'static TextFont>,
+}
+
+impl Typist<'_, '_> {
+    // Mistypes the grapheme at `byte_index` of line `line_index`, which is revealed after
+    // `reveal` graphemes.
+    //
+    // Returns the [`PositionedGlyph`] that displays the typo.
+    pub fn mistype(
+        &self,
+        human: &mut HumanTyping,
+        block: &ComputedTextBlock,
+        glyphs: &Glyphs,
+        line_index: usize,
+        byte_index: usize,
+        reveal: usize,
+    ) -> Option<(PositionedGlyph, Mistyped)> {
+        // Always advance the generator so that typing does not depend on the text.
+        let roll = human.next_f32();
+        let pick = human.next_u64();
+        if human.corrected.take() == Some(reveal) || roll >= non_negative(human.typo_chance) {
+            return None;
+        }
+
+        let text = |glyph: &PositionedGlyph| {
+            &block.buffer().lines[glyph.line_index].text()
+                [glyph.byte_index..glyph.byte_index + glyph.byte_length]
+        };
+        let (entity, (glyph, span)) = glyphs.iter().find_map(|entity| {
+            self.glyphs
+                .get(entity)
+                .ok()
+                .filter(|(glyph, _)| {
+                    glyph.0.line_index == line_index && glyph.0.byte_index == byte_index
+                })
+                .map(|glyph| (entity, glyph))
+        })?;
+
+        let mut chars = text(&glyph.0).chars();
+        let (Some(correct), None) = (chars.next(), chars.next()) else {
+            return None;
+        };
+        let font = self.fonts.get(span.0).ok()?;
+        let same_font = |span: &GlyphSpanEntity| {
+            self.fonts
+                .get(span.0)
+                .is_ok_and(|other| other.font == font.font && other.font_size == font.font_size)
+        };
+
+        let candidates = neighbours(correct)
+            .filter_map(|typo| {
+                glyphs.iter().find_map(|entity| {
+                    let (other, span) = self.glyphs.get(entity).ok()?;
+                    let mut chars = text(&other.0).chars();
+                    (chars.next() == Some(typo) && chars.next().is_none() && same_font(span))
+                        .then_some((typo, other))
+                })
+            })
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return None;
+        }
+        let (typo, other) = candidates[pick as usize % candidates.len()];
+
+        let mut positioned = glyph.0.clone();
+        positioned.atlas_info = other.0.atlas_info.clone();
+        positioned.size = other.0.size;
+        human.corrected = Some(reveal);
+
+        Some((
+            positioned,
+            Mistyped {
+                glyph: entity,
+                text: typo.to_string(),
+                original: glyph.0.clone(),
+                reveal: reveal + 1,
+            },
+        ))
+    }
+}
+
+const KEYBOARD: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+// The keys next to `key` on a QWERTY keyboard, with the case of `key`.
+fn neighbours(key: char) -> impl Iterator<Item = char> {
+    let upper = key.is_uppercase();
+    let lower = key.to_ascii_lowercase();
+    let position = KEYBOARD.iter().enumerate().find_map(|(row, keys)| {
+        keys.chars()
+            .position(|key| key == lower)
+            .map(|column| (row, column as isize))
+    });
+
+    position
+        .into_iter()
+        .flat_map(|(row, column)| {
+            // Rows are staggered, so the keys above are to the right.
+            let above = row.checked_sub(1).map(|row| (row, [column, column + 1]));
+            let below = (row + 1 < KEYBOARD.len()).then_some((row + 1, [column - 1, column]));
+            [(row, [column - 1, column + 1])]
+                .into_iter()
+                .chain(above)
+                .chain(below)
+        })
+        .flat_map(|(row, columns)| {
+            columns
+                .into_iter()
+                .filter_map(move |column| KEYBOARD[row].chars().nth(column.try_into().ok()?))
+        })
+        .map(move |key| if upper { key.to_ascii_uppercase() } else { key })
+}
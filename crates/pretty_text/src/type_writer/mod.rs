@@ -2,24 +2,44 @@
 //! sequencing including:
 //! - Changing speed
 //! - Pausing
+//! - Waiting for input
+//! - Pausing after punctuation
+//! - Typing like a person
 //! - Emitting events
 //! - Running one shot systems
 //!
 //! For more detail, see [`TypeWriter`].
 
+use std::borrow::Cow;
 use std::ops::Range;
 use std::time::Duration;
 
+use bevy::ecs::component::HookContext;
 use bevy::ecs::entity::EntityHashMap;
+use bevy::ecs::system::SystemParam;
+use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
 use bevy::text::ComputedTextBlock;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::PrettyText;
+use crate::dynamic_effects::PrettyTextEffectAppExt;
 use crate::glyph::{Glyph, GlyphOf, GlyphSpanEntity, GlyphSystems, Glyphs};
+use crate::inline_image::{ImageGlyph, TextImage};
 
-use hierarchy::{TypeWriterCallback, TypeWriterCommand, TypeWriterEvent};
+use dynamic_events::DynEventRegistry;
+use hierarchy::{
+    PrettyCallbackRegistry, TypeWriterCallback, TypeWriterCommand, TypeWriterEvent,
+    TypeWriterEventArgs, TypeWriterNamedCallback,
+};
+use human::{HumanTyping, Mistyped, Typist};
+use pacing::{NoPacing, Pace, TypeWriterPacing};
 
+pub mod dynamic_events;
 pub mod hierarchy;
+pub mod human;
+pub mod pacing;
+pub mod timeline;
 
 /// A plugin for managing [`TypeWriter`] entities.
 #[derive(Debug)]
@@ -28,41 +48,69 @@ pub struct TypeWriterPlugin;
 impl Plugin for TypeWriterPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<GlyphRevealed>()
+            .add_event::<GlyphErased>()
             .add_event::<TypeWriterFinished>()
             .add_event::<TypeWriterEvent>()
+            .init_resource::<DynEventRegistry>()
+            .init_resource::<PrettyCallbackRegistry>()
+            .register_pretty_effect::<NoPacing>(NoPacing::TAG)
             .register_type::<TypeWriterCommand>()
             .add_systems(
                 PostUpdate,
                 (
                     calculate_byte_range,
+                    timeline::compile_timelines,
+                    timeline::seek_timelines,
                     type_writer,
                     reveal_glyphs.after(GlyphSystems::Construct),
                 )
                     .chain(),
             )
-            .add_observer(removed_reveal);
+            .add_observer(removed_reveal)
+            .add_observer(release_signal)
+            .add_observer(human::restore_mistyped);
 
         app.register_type::<TypeWriter>()
             .register_type::<TypeWriterMode>()
+            .register_type::<WordSegmentation>()
             .register_type::<TypeWriterFinished>()
             .register_type::<GlyphRevealed>()
+            .register_type::<GlyphErased>()
+            .register_type::<WordRevealed>()
+            .register_type::<LineRevealed>()
+            .register_type::<SentenceRevealed>()
             .register_type::<PauseTypeWriter>()
+            .register_type::<TypeWriterWaiting>()
+            .register_type::<WaitingForSignal>()
+            .register_type::<TypeWriterSignal>()
             .register_type::<Reveal>()
             .register_type::<TypeWriterCommand>()
-            .register_type::<TypeWriterEvent>();
+            .register_type::<TypeWriterEvent>()
+            .register_type::<TypeWriterEventArgs>()
+            .register_type::<TypeWriterNamedCallback>()
+            .register_type::<TypeWriterPacing>()
+            .register_type::<NoPacing>()
+            .register_type::<HumanTyping>()
+            .register_type::<timeline::TypeWriterTimeline>();
     }
 }
 
 /// Controls the visibility of [`Glyph`]s in a text hierarchy.
 ///
+/// `Reveal` counts [grapheme clusters], the characters that a reader sees. A
+/// letter with combining marks, an emoji sequence and a CJK ideograph are each
+/// a single grapheme cluster.
+///
+/// [grapheme clusters]: https://www.unicode.org/reports/tr29/#Grapheme_Cluster_Boundaries
+///
 /// ```
 /// # use bevy::prelude::*;
 /// # use pretty_text::type_writer::*;
 /// #
 /// # let mut world = World::new();
-/// // Reveal 3 glyphs.
+/// // Reveal 3 graphemes.
 /// world.spawn((
-///     Reveal(3),
+///     Reveal::graphemes(3),
 ///     Text2d::new("my text"),
 /// ));
 ///
@@ -82,6 +130,12 @@ impl Reveal {
 
     /// Hide all [`Glyph`]s in a text hierarchy.
     pub const NONE: Self = Self(0);
+
+    /// Reveal `n` grapheme clusters.
+    #[inline]
+    pub const fn graphemes(n: usize) -> Self {
+        Self(n)
+    }
 }
 
 /// [`TypeWriter`] reveals text over time.
@@ -116,6 +170,7 @@ impl Reveal {
 /// effects include:
 /// - Changing speed
 /// - Pausing
+/// - Waiting for input
 /// - Emitting events
 /// - Running one shot systems
 ///
@@ -126,11 +181,14 @@ impl Reveal {
 /// # Revealing Text
 ///
 /// The [`TypeWriter`] uses the [`Reveal`] component to control how many glyphs are visible at a time.
-/// [`TypeWriter`]s can reveal either glyphs (the default) or words, configurable with [`TypeWriterMode`].
+/// [`TypeWriter`]s can reveal glyphs (the default), words, lines or sentences, configurable with
+/// [`TypeWriterMode`].
 ///
 /// The [`TypeWriter`] entity will trigger events related to the revealed text:
 /// - [`GlyphRevealed`] (when configured with [`TypeWriterMode::Glyph`])
 /// - [`WordRevealed`] (when configured with [`TypeWriterMode::Word`])
+/// - [`LineRevealed`] (when configured with [`TypeWriterMode::Line`])
+/// - [`SentenceRevealed`] (when configured with [`TypeWriterMode::Sentence`])
 ///
 /// You can observe these events to, for example, play a sound:
 /// ```
@@ -169,8 +227,8 @@ impl Reveal {
 /// # use pretty_text::type_writer::*;
 /// #
 /// fn short_circuit(mut commands: Commands, typewriter: Single<Entity, With<TypeWriter>>) {
-///     // Removing the typewriter will reveal remaining glyphs but *skip*
-///     // any untriggered events or callbacks.
+///     // Removing the typewriter will reveal remaining glyphs and clear any
+///     // pause or wait, but *skip* any untriggered events or callbacks.
 ///     commands.entity(*typewriter).remove::<TypeWriter>();
 /// }
 ///
@@ -182,21 +240,82 @@ impl Reveal {
 /// ```
 ///
 /// In both cases, a [`TypeWriterFinished`] event will be triggered.
+///
+/// # Waiting for Input
+///
+/// The `[wait]` command holds the [`TypeWriter`] until [`TypeWriter::advance`] is called.
+/// While held, the entity has a [`TypeWriterWaiting`] component.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use pretty_text::type_writer::*;
+#[doc = include_str!("../../docs/pretty.txt")]
+/// #
+/// # let mut world = World::new();
+/// world.spawn((
+///     TypeWriter::new(30.0),
+///     pretty!("The door creaks...[wait] and opens."),
+/// ));
+///
+/// fn continue_on_click(
+///     input: Res<ButtonInput<MouseButton>>,
+///     mut typewriters: Query<&mut TypeWriter, With<TypeWriterWaiting>>,
+/// ) {
+///     if input.just_pressed(MouseButton::Left) {
+///         for mut typewriter in typewriters.iter_mut() {
+///             typewriter.advance();
+///         }
+///     }
+/// }
+/// ```
+///
+/// The `{wait:tag}` command holds the [`TypeWriter`] until a [`TypeWriterSignal`] with a
+/// matching tag is triggered. A timeout in seconds continues the [`TypeWriter`] if the
+/// signal is not triggered in time: `{wait:tag:2.5}`. While held, the entity has a
+/// [`WaitingForSignal`] component.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use pretty_text::type_writer::*;
+#[doc = include_str!("../../docs/pretty.txt")]
+/// #
+/// # let mut world = World::new();
+/// world.spawn((
+///     TypeWriter::new(30.0),
+///     pretty!("The door creaks...{wait:door_open} and opens."),
+/// ));
+///
+/// fn door_opened(mut commands: Commands) {
+///     commands.trigger(TypeWriterSignal::new("door_open"));
+/// }
+/// ```
+///
+/// # Pacing
+///
+/// A [`TypeWriterPacing`] pauses the [`TypeWriter`] after punctuation. See
+/// [`pacing`].
+///
+/// A [`HumanTyping`] varies the speed of the [`TypeWriter`] and makes typos that are
+/// erased with [`GlyphErased`]. See [`human`].
 #[derive(Debug, Clone, Component, Reflect)]
 #[require(PrettyText, TypeWriterMode, Reveal)]
+#[component(on_remove = remove_sequence_state)]
 pub struct TypeWriter {
     speed: f32,
     timer: Timer,
     processed_children: Vec<Entity>,
     finish: bool,
+    advance: bool,
 }
 
 impl TypeWriter {
     /// Creates a new `TypeWriter` with `speed`.
     ///
     /// The unit of `speed` is:
-    /// - glyphs/second (when configured with [`TypeWriterMode::Glyph`])
+    /// - graphemes/second (when configured with [`TypeWriterMode::Glyph`])
     /// - words/second (when configured with [`TypeWriterMode::Word`])
+    /// - lines/second (when configured with [`TypeWriterMode::Line`])
+    /// - sentences/second (when configured with [`TypeWriterMode::Sentence`])
     #[inline]
     pub fn new(speed: f32) -> Self {
         Self {
@@ -204,6 +323,7 @@ impl TypeWriter {
             timer: Self::new_timer(speed),
             processed_children: Vec::new(),
             finish: false,
+            advance: false,
         }
     }
 
@@ -219,6 +339,13 @@ impl TypeWriter {
         self.finish = true;
     }
 
+    /// Continues a `TypeWriter` that is [waiting for input](TypeWriterWaiting).
+    ///
+    /// Has no effect if the `TypeWriter` is not waiting.
+    pub fn advance(&mut self) {
+        self.advance = true;
+    }
+
     #[inline]
     fn new_timer(speed: f32) -> Timer {
         let dur = 1.0 / speed;
@@ -239,22 +366,243 @@ pub enum TypeWriterMode {
     Glyph,
 
     /// A collection of [`Glyph`]s.
+    ///
+    /// Words are found with [Unicode word boundaries], configurable with
+    /// [`WordSegmentation`]. Punctuation is revealed with its word.
+    ///
+    /// [Unicode word boundaries]: https://www.unicode.org/reports/tr29/#Word_Boundaries
     Word,
+
+    /// A line of the text layout, including lines that are wrapped.
+    Line,
+
+    /// A sentence, ending with terminal punctuation such as `.`, `!` or `?`.
+    ///
+    /// Sentences are found with [Unicode sentence boundaries].
+    ///
+    /// [Unicode sentence boundaries]: https://www.unicode.org/reports/tr29/#Sentence_Boundaries
+    Sentence,
 }
 
-/// An event triggered by a [`TypeWriter`] entity when a [`Glyph`] is revealed.
+/// Configures how a [`TypeWriter`] in [`TypeWriterMode::Word`] splits text into words.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use pretty_text::type_writer::*;
+/// #
+/// # let mut world = World::new();
+/// world.spawn((
+///     TypeWriter::new(8.0),
+///     TypeWriterMode::Word,
+///     WordSegmentation::Ideographic,
+///     Text2d::new("カタカナで書く"),
+/// ));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component, Reflect)]
+pub enum WordSegmentation {
+    /// Split words on [Unicode word boundaries].
+    ///
+    /// Without a dictionary, ideographs and hiragana are split into single
+    /// characters, while runs of katakana are kept together.
+    ///
+    /// [Unicode word boundaries]: https://www.unicode.org/reports/tr29/#Word_Boundaries
+    #[default]
+    Unicode,
+
+    /// Split words on Unicode word boundaries, and treat every ideograph and
+    /// kana as a word.
+    Ideographic,
+}
+
+impl WordSegmentation {
+    // Splits `text` into segments with their byte index.
+    fn segments(self, text: &str) -> Vec<(usize, &str)> {
+        text.split_word_bound_indices()
+            .flat_map(|(i, segment)| match self {
+                Self::Ideographic if segment.chars().any(is_cjk) => segment
+                    .grapheme_indices(true)
+                    .map(|(j, grapheme)| (i + j, grapheme))
+                    .collect(),
+                _ => vec![(i, segment)],
+            })
+            .collect()
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}'
+            | '\u{31F0}'..='\u{31FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF66}'..='\u{FF9F}'
+            | '\u{20000}'..='\u{3134F}'
+    )
+}
+
+// The next unit of text revealed by a type writer.
+struct Unit<'a> {
+    line_index: usize,
+    // Byte offset of the line from the start of the block.
+    line_offset: usize,
+    // Byte range of the unit in its line, without surrounding whitespace.
+    range: Range<usize>,
+    text: &'a str,
+    // The number of graphemes revealed, including surrounding whitespace.
+    graphemes: usize,
+}
+
+// Finds the next unit of text after `reveal` graphemes.
+fn next_unit(
+    block: &ComputedTextBlock,
+    mode: TypeWriterMode,
+    segmentation: WordSegmentation,
+    reveal: usize,
+) -> Option<Unit<'_>> {
+    unit_from(block, mode, segmentation, graphemes(block).skip(reveal))
+}
+
+// Finds the next unit of text in the `remaining` graphemes of `block`.
+fn unit_from<'a>(
+    block: &'a ComputedTextBlock,
+    mode: TypeWriterMode,
+    segmentation: WordSegmentation,
+    remaining: impl Iterator<Item = Grapheme<'a>>,
+) -> Option<Unit<'a>> {
+    let mut remaining = remaining.peekable();
+    let next = *remaining.peek()?;
+
+    let text = block.buffer().lines[next.line_index].text();
+    let (range, consumed) = match mode {
+        TypeWriterMode::Glyph => (0..next.text.len(), next.text.len()),
+        TypeWriterMode::Word => next_word(&text[next.byte_index..], segmentation),
+        TypeWriterMode::Line => {
+            let end =
+                visual_line_end(block, next.line_index, next.byte_index).unwrap_or(text.len());
+            next_trimmed(&text[next.byte_index..end])
+        }
+        TypeWriterMode::Sentence => next_sentence(&text[next.byte_index..]),
+    };
+    let graphemes = remaining
+        .take_while(|grapheme| {
+            grapheme.line_index == next.line_index
+                && grapheme.byte_index < next.byte_index + consumed
+        })
+        .count();
+
+    let range = next.byte_index + range.start..next.byte_index + range.end;
+    Some(Unit {
+        line_index: next.line_index,
+        line_offset: next.line_offset,
+        text: &text[range.clone()],
+        range,
+        graphemes,
+    })
+}
+
+// Finds the next word in `text`.
+//
+// Returns the byte range of the word and the end of the whitespace that follows it.
+fn next_word(text: &str, segmentation: WordSegmentation) -> (Range<usize>, usize) {
+    let is_whitespace = |(_, segment): &(usize, &str)| segment.chars().all(char::is_whitespace);
+    let is_word = |(_, segment): &(usize, &str)| segment.chars().any(char::is_alphanumeric);
+    let end_of = |(i, segment): (usize, &str)| i + segment.len();
+
+    let mut segments = segmentation.segments(text).into_iter().peekable();
+    let mut start = 0;
+    while let Some(segment) = segments.next_if(is_whitespace) {
+        start = end_of(segment);
+    }
+
+    let mut end = start;
+    let mut has_word = false;
+    // Punctuation belongs to the word that it follows or precedes.
+    while let Some(segment) =
+        segments.next_if(|segment| !(is_whitespace(segment) || has_word && is_word(segment)))
+    {
+        has_word |= is_word(&segment);
+        end = end_of(segment);
+    }
+
+    let mut consumed = end;
+    while let Some(segment) = segments.next_if(is_whitespace) {
+        consumed = end_of(segment);
+    }
+
+    (start..end, consumed)
+}
+
+// Finds the next sentence in `text`, split on terminal punctuation.
+//
+// Returns the byte range of the sentence and the end of the whitespace that follows it.
+fn next_sentence(text: &str) -> (Range<usize>, usize) {
+    let sentence = text
+        .split_sentence_bounds()
+        .find(|sentence| !sentence.trim().is_empty())
+        .map(|sentence| sentence.as_ptr() as usize - text.as_ptr() as usize + sentence.len())
+        .unwrap_or(text.len());
+    next_trimmed(&text[..sentence])
+}
+
+// Returns the byte range of `text` without surrounding whitespace and the length of `text`.
+fn next_trimmed(text: &str) -> (Range<usize>, usize) {
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len().max(start);
+    (start..end, text.len())
+}
+
+// The end of the visual line that contains `byte_index` in the line `line_index`.
+//
+// Returns `None` if `byte_index` is on the last visual line of `line_index`.
+fn visual_line_end(
+    block: &ComputedTextBlock,
+    line_index: usize,
+    byte_index: usize,
+) -> Option<usize> {
+    block
+        .buffer()
+        .layout_runs()
+        .filter(|run| run.line_i == line_index)
+        .filter_map(|run| run.glyphs.iter().map(|glyph| glyph.start).min())
+        .filter(|start| *start > byte_index)
+        .min()
+}
+
+/// An event triggered by a [`TypeWriter`] entity when a grapheme cluster is revealed.
 ///
 /// `GlyphRevealed` is only triggered when the [`TypeWriter`] entity is configured with
 /// [`TypeWriterMode::Glyph`].
 #[derive(Debug, Clone, Event, Reflect)]
 pub struct GlyphRevealed {
-    /// The revealed [`Glyph`].
+    /// The first revealed [`Glyph`] of the grapheme cluster.
     ///
     /// `glyph` can be `None` when the original text is whitespace and stripped from the
     /// underlying cosmic buffer.
     pub glyph: Option<Entity>,
 
-    /// The text represented by the [`Glyph`].
+    /// The grapheme cluster represented by the [`Glyph`].
+    ///
+    /// For an [inline image](crate::inline_image), this is the placeholder
+    /// character of its span, see [`GlyphRevealed::image`].
+    pub text: String,
+
+    /// The name of the [inline image](crate::inline_image) if the [`Glyph`] is an
+    /// [`ImageGlyph`].
+    pub image: Option<Cow<'static, str>>,
+}
+
+/// An event triggered by a [`TypeWriter`] entity when a mistyped grapheme cluster is
+/// erased.
+///
+/// `GlyphErased` is only triggered when the [`TypeWriter`] entity has a [`HumanTyping`].
+#[derive(Debug, Clone, Event, Reflect)]
+pub struct GlyphErased {
+    /// The [`Glyph`] that displayed the typo.
+    pub glyph: Entity,
+
+    /// The mistyped grapheme cluster.
     pub text: String,
 }
 
@@ -271,6 +619,32 @@ pub struct WordRevealed {
     pub text: String,
 }
 
+/// An event triggered by a [`TypeWriter`] entity when a line is revealed.
+///
+/// `LineRevealed` is only triggered when the [`TypeWriter`] entity is configured with
+/// [`TypeWriterMode::Line`].
+#[derive(Debug, Clone, Event, Reflect)]
+pub struct LineRevealed {
+    /// The revealed collection of [`Glyph`]s.
+    pub glyphs: Vec<Entity>,
+
+    /// The text represented by the line.
+    pub text: String,
+}
+
+/// An event triggered by a [`TypeWriter`] entity when a sentence is revealed.
+///
+/// `SentenceRevealed` is only triggered when the [`TypeWriter`] entity is configured with
+/// [`TypeWriterMode::Sentence`].
+#[derive(Debug, Clone, Event, Reflect)]
+pub struct SentenceRevealed {
+    /// The revealed collection of [`Glyph`]s.
+    pub glyphs: Vec<Entity>,
+
+    /// The text represented by the sentence.
+    pub text: String,
+}
+
 /// An event triggered by a [`TypeWriter`] entity when the entire text hierarchy is revealed.
 #[derive(Debug, Clone, Copy, Event, Reflect)]
 pub struct TypeWriterFinished;
@@ -287,6 +661,78 @@ impl PauseTypeWriter {
     }
 }
 
+/// Marks a [`TypeWriter`] that is held by a [`TypeWriterCommand::WaitForInput`].
+///
+/// `TypeWriterWaiting` is removed when [`TypeWriter::advance`] is called or the
+/// [`TypeWriter`] finishes. Observe [`OnAdd`] and [`OnRemove`] to, for example, show
+/// a continue arrow.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct TypeWriterWaiting;
+
+/// Marks a [`TypeWriter`] that is held by a [`TypeWriterCommand::WaitFor`].
+///
+/// `WaitingForSignal` is removed when a matching [`TypeWriterSignal`] is triggered, the
+/// timeout elapses, or the [`TypeWriter`] finishes.
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct WaitingForSignal {
+    /// The tag of the signal.
+    pub signal: String,
+
+    /// Continues the [`TypeWriter`] when finished.
+    pub timeout: Option<Timer>,
+}
+
+impl WaitingForSignal {
+    /// Creates a new wait for `signal` with an optional timeout in seconds.
+    #[inline]
+    pub fn new(signal: impl Into<String>, timeout: Option<f32>) -> Self {
+        Self {
+            signal: signal.into(),
+            timeout: timeout.map(|timeout| Timer::from_seconds(timeout, TimerMode::Once)),
+        }
+    }
+}
+
+/// Releases [`TypeWriter`]s that are [waiting for](WaitingForSignal) a signal with the
+/// same tag.
+///
+/// Trigger `TypeWriterSignal` globally to release every matching [`TypeWriter`], or
+/// target an entity to release only that [`TypeWriter`]. A signal that is triggered
+/// before a [`TypeWriter`] waits for it is ignored.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use pretty_text::type_writer::*;
+/// #
+/// fn door_opened(mut commands: Commands) {
+///     commands.trigger(TypeWriterSignal::new("door_open"));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Event, Deref, Reflect)]
+pub struct TypeWriterSignal(pub String);
+
+impl TypeWriterSignal {
+    /// Creates a new signal with a tag.
+    #[inline]
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self(tag.into())
+    }
+}
+
+fn release_signal(
+    trigger: Trigger<TypeWriterSignal>,
+    mut commands: Commands,
+    waiting: Query<(Entity, &WaitingForSignal)>,
+) {
+    let target = trigger.target();
+    for (entity, wait) in waiting.iter() {
+        if wait.signal == trigger.0 && (target == Entity::PLACEHOLDER || target == entity) {
+            debug!("type writer {entity} received signal `{}`", wait.signal);
+            commands.entity(entity).remove::<WaitingForSignal>();
+        }
+    }
+}
+
 fn reveal_glyphs(
     reveal: Query<
         (&Glyphs, &ComputedTextBlock, &Reveal),
@@ -295,6 +741,7 @@ fn reveal_glyphs(
     mut visibilities: Query<(&mut Visibility, &Glyph), With<GlyphOf>>,
 ) {
     for (glyphs, block, reveal) in reveal.iter() {
+        let revealed = revealed_bytes(block, reveal.0);
         for entity in glyphs.iter() {
             if let Ok((mut vis, glyph)) = visibilities.get_mut(entity) {
                 let line_offset = block
@@ -305,7 +752,7 @@ fn reveal_glyphs(
                     .map(|line| line.text().len())
                     .sum::<usize>();
 
-                let target = if line_offset + glyph.0.byte_index + glyph.0.byte_length <= reveal.0 {
+                let target = if line_offset + glyph.0.byte_index + glyph.0.byte_length <= revealed {
                     Visibility::Inherited
                 } else {
                     Visibility::Hidden
@@ -319,6 +766,16 @@ fn reveal_glyphs(
     }
 }
 
+// A `TypeWriter` that is removed early must not leave a pause, wait or typo behind.
+fn remove_sequence_state(mut world: DeferredWorld, ctx: HookContext) {
+    world.commands().entity(ctx.entity).try_remove::<(
+        Mistyped,
+        TypeWriterWaiting,
+        WaitingForSignal,
+        PauseTypeWriter,
+    )>();
+}
+
 fn removed_reveal(
     trigger: Trigger<OnRemove, Reveal>,
     mut visibilities: Query<&mut Visibility, With<GlyphOf>>,
@@ -335,6 +792,103 @@ fn removed_reveal(
     }
 }
 
+// A grapheme cluster in a `ComputedTextBlock`.
+#[derive(Clone, Copy)]
+struct Grapheme<'a> {
+    line_index: usize,
+    // Byte offset of the line from the start of the block.
+    line_offset: usize,
+    // Byte offset from the start of the line.
+    byte_index: usize,
+    text: &'a str,
+}
+
+fn graphemes(block: &ComputedTextBlock) -> impl Iterator<Item = Grapheme<'_>> {
+    let mut line_offset = 0;
+    block
+        .buffer()
+        .lines
+        .iter()
+        .enumerate()
+        .flat_map(move |(line_index, line)| {
+            let offset = line_offset;
+            line_offset += line.text().len();
+            line.text()
+                .grapheme_indices(true)
+                .map(move |(byte_index, text)| Grapheme {
+                    line_index,
+                    line_offset: offset,
+                    byte_index,
+                    text,
+                })
+        })
+}
+
+// The pace after `unit`, where `next` is the grapheme that follows `unit`.
+//
+// Units that end in one of the `no_pacing` byte ranges are not paced.
+fn unit_pace(
+    pacing: &TypeWriterPacing,
+    unit: &Unit,
+    next: Option<Grapheme>,
+    no_pacing: &[Range<usize>],
+) -> Option<Pace> {
+    let grapheme = unit.text.graphemes(true).next_back()?;
+    let end = unit.line_offset + unit.range.end;
+    if no_pacing.iter().any(|range| range.contains(&(end - 1))) {
+        return None;
+    }
+
+    // The end of the text is not paced.
+    let next = next?;
+    pacing.pace(grapheme, next.line_index != unit.line_index)
+}
+
+// The number of bytes covered by the first `graphemes` grapheme clusters of `block`.
+fn revealed_bytes(block: &ComputedTextBlock, graphemes: usize) -> usize {
+    byte_offset(block, self::graphemes(block).nth(graphemes))
+}
+
+// The byte offset of `grapheme` from the start of `block`, or the length of `block`
+// if there is no `grapheme`.
+fn byte_offset(block: &ComputedTextBlock, grapheme: Option<Grapheme>) -> usize {
+    grapheme
+        .map(|grapheme| grapheme.line_offset + grapheme.byte_index)
+        .unwrap_or_else(|| {
+            block
+                .buffer()
+                .lines
+                .iter()
+                .map(|line| line.text().len())
+                .sum()
+        })
+}
+
+// A child of the text hierarchy, in the terms of `holds`.
+enum PaceChild<'a> {
+    Span(usize),
+    Command(&'a TypeWriterCommand),
+    Other,
+}
+
+// Returns `true` if a pause or wait command is reached in `children` before the span
+// that ends after `revealed` bytes.
+//
+// An explicit pause or wait replaces the pace of the last revealed unit.
+fn holds<'a>(revealed: usize, children: impl IntoIterator<Item = PaceChild<'a>>) -> bool {
+    for child in children {
+        match child {
+            PaceChild::Span(end) if end > revealed => return false,
+            PaceChild::Command(command) if !matches!(command, TypeWriterCommand::Speed(_)) => {
+                return true;
+            }
+            _ => {}
+        }
+    }
+
+    false
+}
+
 #[derive(Default, Component)]
 struct ByteRange(Range<usize>);
 
@@ -391,13 +945,192 @@ fn calculate_byte_range(
             })
             .max()
             .unwrap();
-        commands.entity(span_entity).insert(ByteRange(start..end));
+        // The span may be despawned when its text hierarchy is rebuilt.
+        commands
+            .entity(span_entity)
+            .try_insert(ByteRange(start..end));
     }
 
     Ok(())
 }
 
-// TODO: The type writer reveals codepoint-by-codepoint and not glyph-by-glyph because some of the
+#[derive(SystemParam)]
+struct Sequence<'w, 's> {
+    writer: EventWriter<'w, TypeWriterEvent>,
+    spans: Query<'w, 's, &'static ByteRange, With<TextSpan>>,
+    effects: Query<'w, 's, &'static TypeWriterCommand>,
+    events: Query<
+        'w,
+        's,
+        (
+            &'static TypeWriterEvent,
+            Option<&'static TypeWriterEventArgs>,
+        ),
+    >,
+    callbacks: Query<'w, 's, &'static TypeWriterCallback>,
+    named_callbacks: Query<'w, 's, &'static TypeWriterNamedCallback>,
+    registry: Res<'w, DynEventRegistry>,
+    callback_registry: Res<'w, PrettyCallbackRegistry>,
+}
+
+impl Sequence<'_, '_> {
+    // Triggers and emits the event of `child`, and triggers its dynamic event.
+    fn fire_event(&mut self, commands: &mut Commands, entity: Entity, child: Entity) -> bool {
+        let Ok((event, args)) = self.events.get(child) else {
+            return false;
+        };
+
+        self.writer.write(event.clone());
+        commands.entity(entity).trigger(event.clone());
+        self.registry
+            .trigger(event, args, &mut commands.entity(entity));
+        true
+    }
+
+    // Queues the callback or named callback of `child`.
+    fn run_callback(&self, commands: &mut Commands, child: Entity) -> bool {
+        if let Ok(callback) = self.callbacks.get(child) {
+            callback.queue(commands);
+            return true;
+        }
+
+        let Ok(callback) = self.named_callbacks.get(child) else {
+            return false;
+        };
+        match self.callback_registry.get(&callback.0) {
+            Some(system) => commands.run_system(system),
+            None => error!("callback `{}` is not registered", callback.0),
+        }
+        true
+    }
+
+    // Processes the commands, events and callbacks that are reached after `revealed` bytes.
+    //
+    // Returns `true` if the type writer is paused.
+    fn advance(
+        &mut self,
+        commands: &mut Commands,
+        entity: Entity,
+        tw: &mut TypeWriter,
+        revealed: Option<usize>,
+        children: Option<&Children>,
+    ) -> bool {
+        let Some(children) = children else {
+            return false;
+        };
+
+        for child in children.iter() {
+            if tw.processed_children.contains(&child) {
+                continue;
+            }
+
+            if let Ok(range) = self.spans.get(child) {
+                if revealed.is_none_or(|revealed| range.0.end > revealed) {
+                    break;
+                }
+                continue;
+            }
+            //
+            else if let Ok(effect) = self.effects.get(child) {
+                tw.processed_children.push(child);
+                match *effect {
+                    TypeWriterCommand::Pause(dur) => {
+                        commands
+                            .entity(entity)
+                            .insert(PauseTypeWriter::from_seconds(dur));
+                        return true;
+                    }
+                    TypeWriterCommand::WaitForInput => {
+                        commands.entity(entity).insert(TypeWriterWaiting);
+                        return true;
+                    }
+                    TypeWriterCommand::WaitFor {
+                        ref signal,
+                        timeout,
+                    } => {
+                        debug!("type writer {entity} waiting for signal `{signal}`");
+                        commands
+                            .entity(entity)
+                            .insert(WaitingForSignal::new(signal, timeout));
+                        return true;
+                    }
+                    TypeWriterCommand::Speed(mult) => {
+                        let speed = tw.speed;
+                        tw.timer
+                            .set_duration(Duration::from_secs_f32(1. / speed / mult));
+                    }
+                }
+            }
+            //
+            else if self.run_callback(commands, child) || self.fire_event(commands, entity, child)
+            {
+                tw.processed_children.push(child);
+            }
+        }
+
+        false
+    }
+
+    // Returns `true` if a pause or wait command is reached after `revealed` bytes.
+    fn holds(&self, tw: &TypeWriter, revealed: usize, children: Option<&Children>) -> bool {
+        let children = children
+            .into_iter()
+            .flatten()
+            .filter(|child| !tw.processed_children.contains(*child))
+            .map(|child| {
+                if let Ok(range) = self.spans.get(*child) {
+                    PaceChild::Span(range.0.end)
+                } else if let Ok(effect) = self.effects.get(*child) {
+                    PaceChild::Command(effect)
+                } else {
+                    PaceChild::Other
+                }
+            });
+        holds(revealed, children)
+    }
+
+    // Processes all remaining commands, events and callbacks.
+    fn finish(
+        &mut self,
+        commands: &mut Commands,
+        entity: Entity,
+        tw: &mut TypeWriter,
+        children: Option<&Children>,
+    ) {
+        let Some(children) = children else {
+            return;
+        };
+
+        for child in children.iter() {
+            if tw.processed_children.contains(&child) {
+                continue;
+            }
+
+            if let Ok(effect) = self.effects.get(child) {
+                match *effect {
+                    TypeWriterCommand::Pause(dur) => {
+                        commands
+                            .entity(entity)
+                            .insert(PauseTypeWriter::from_seconds(dur));
+                    }
+                    // The remaining text is revealed all at once.
+                    TypeWriterCommand::WaitForInput | TypeWriterCommand::WaitFor { .. } => {}
+                    TypeWriterCommand::Speed(mult) => {
+                        let speed = tw.speed;
+                        tw.timer
+                            .set_duration(Duration::from_secs_f32(1. / speed / mult));
+                    }
+                }
+            }
+            //
+            else if !self.run_callback(commands, child) {
+                self.fire_event(commands, entity, child);
+            }
+        }
+    }
+}
+
+// NOTE: The type writer reveals grapheme-by-grapheme and not glyph-by-glyph because some of the
 // glyphs are stripped by the layout system.
 fn type_writer(
     mut commands: Commands,
@@ -410,52 +1143,40 @@ fn type_writer(
         &mut TypeWriter,
         Mut<Reveal>,
         Option<&mut PauseTypeWriter>,
+        Has<TypeWriterWaiting>,
+        Option<&mut WaitingForSignal>,
         Option<&Children>,
+        Option<&WordSegmentation>,
+        Option<&TypeWriterPacing>,
+        Option<&mut HumanTyping>,
+        Option<&Mistyped>,
     )>,
-    mut writer: EventWriter<TypeWriterEvent>,
     glyph_query: Query<&Glyph>,
-    spans: Query<&ByteRange, With<TextSpan>>,
-    effects: Query<&TypeWriterCommand>,
-    events: Query<&TypeWriterEvent>,
-    callbacks: Query<&TypeWriterCallback>,
+    image_glyphs: Query<&GlyphSpanEntity, With<ImageGlyph>>,
+    text_images: Query<&TextImage>,
+    no_pacing: Query<&ByteRange, With<NoPacing>>,
+    typist: Typist,
+    mut sequence: Sequence,
 ) -> Result {
-    for (entity, glyphs, block, mode, mut tw, mut reveal, pause, children) in
-        type_writers.iter_mut()
+    for (
+        entity,
+        glyphs,
+        block,
+        mode,
+        mut tw,
+        mut reveal,
+        pause,
+        waiting,
+        signal,
+        children,
+        segmentation,
+        pacing,
+        mut human,
+        mistyped,
+    ) in type_writers.iter_mut()
     {
         if tw.finish {
-            if let Some(children) = children {
-                for child in children.iter() {
-                    if tw.processed_children.contains(&child) {
-                        continue;
-                    }
-
-                    if let Ok(effect) = effects.get(child) {
-                        match *effect {
-                            TypeWriterCommand::Pause(dur) => {
-                                commands
-                                    .entity(entity)
-                                    .insert(PauseTypeWriter::from_seconds(dur));
-                            }
-                            TypeWriterCommand::Speed(mult) => {
-                                let speed = tw.speed;
-                                tw.timer
-                                    .set_duration(Duration::from_secs_f32(1. / speed / mult));
-                            }
-                        }
-                    }
-                    //
-                    else if let Ok(callback) = callbacks.get(child) {
-                        callback.queue(&mut commands);
-                    }
-                    //
-                    else if let Ok(event) = events.get(child) {
-                        let event = TypeWriterEvent(event.0.clone());
-                        writer.write(event.clone());
-                        commands.entity(entity).trigger(event.clone());
-                    }
-                }
-            }
-
+            sequence.finish(&mut commands, entity, &mut tw, children);
             commands
                 .entity(entity)
                 .remove::<(TypeWriter, TypeWriterMode, Reveal)>()
@@ -463,6 +1184,31 @@ fn type_writer(
             continue;
         }
 
+        // Input received before the type writer waits is ignored.
+        if tw.advance {
+            tw.advance = false;
+            if waiting {
+                commands.entity(entity).remove::<TypeWriterWaiting>();
+            }
+        } else if waiting {
+            continue;
+        }
+
+        if let Some(mut signal) = signal {
+            let Some(timeout) = signal.timeout.as_mut() else {
+                continue;
+            };
+            timeout.tick(time.delta());
+            if !timeout.finished() {
+                continue;
+            }
+            debug!(
+                "type writer {entity} timed out waiting for `{}`",
+                signal.signal
+            );
+            commands.entity(entity).remove::<WaitingForSignal>();
+        }
+
         if let Some(mut pause) = pause {
             pause.0.tick(time.delta());
             if pause.0.finished() {
@@ -472,76 +1218,32 @@ fn type_writer(
             }
         }
 
-        // TODO: this doesn't need to happen every update
-        let mut should_pause = false;
-        if let Some(children) = children {
-            for child in children.iter() {
-                if tw.processed_children.contains(&child) {
-                    continue;
-                }
-
-                if let Ok(range) = spans.get(child) {
-                    if reveal.0 == 0 || range.0.end > reveal.0 {
-                        break;
-                    }
-                    continue;
-                }
-                //
-                else if let Ok(effect) = effects.get(child) {
-                    tw.processed_children.push(child);
-                    match *effect {
-                        TypeWriterCommand::Pause(dur) => {
-                            commands
-                                .entity(entity)
-                                .insert(PauseTypeWriter::from_seconds(dur));
-                            should_pause = true;
-                            break;
-                        }
-                        TypeWriterCommand::Speed(mult) => {
-                            let speed = tw.speed;
-                            tw.timer
-                                .set_duration(Duration::from_secs_f32(1. / speed / mult));
-                        }
-                    }
-                }
-                //
-                else if let Ok(callback) = callbacks.get(child) {
-                    tw.processed_children.push(child);
-                    callback.queue(&mut commands);
-                }
-                //
-                else if let Ok(event) = events.get(child) {
-                    tw.processed_children.push(child);
-                    let event = TypeWriterEvent(event.0.clone());
-                    writer.write(event.clone());
-                    commands.entity(entity).trigger(event.clone());
-
-                    continue;
-                }
+        // A typo is erased once its pause is over.
+        if let Some(mistyped) = mistyped {
+            // A seek may have moved the type writer.
+            if reveal.0 == mistyped.reveal {
+                reveal.0 -= 1;
             }
+            commands
+                .entity(entity)
+                .remove::<Mistyped>()
+                .trigger(GlyphErased {
+                    glyph: mistyped.glyph,
+                    text: mistyped.text.clone(),
+                });
+            tw.timer.reset();
+            continue;
         }
 
-        if should_pause {
+        // Nothing is reached before the first grapheme is revealed.
+        let revealed = |reveal: &Reveal| (reveal.0 != 0).then(|| revealed_bytes(block, reveal.0));
+
+        // TODO: this doesn't need to happen every update
+        if sequence.advance(&mut commands, entity, &mut tw, revealed(&reveal), children) {
             continue;
         }
 
-        let mut accum = 0;
-        let mut line_offset = 0;
-        let line_index = block
-            .buffer()
-            .lines
-            .iter()
-            .take_while(|line| {
-                accum += line.text().len();
-                let take = accum <= reveal.0;
-                if take {
-                    line_offset += line.text().len();
-                }
-                take
-            })
-            .count();
-
-        if reveal.0 >= accum {
+        if graphemes(block).nth(reveal.0).is_none() {
             commands
                 .entity(entity)
                 .remove::<(TypeWriter, TypeWriterMode, Reveal)>()
@@ -549,84 +1251,581 @@ fn type_writer(
             continue;
         }
 
+        // Several units can be revealed in a single frame at high speeds or low frame rates.
         tw.timer.tick(time.delta());
-        if tw.timer.just_finished() {
+        let mut ticks = tw.timer.times_finished_this_tick();
+        let mut i = 0;
+        while i < ticks {
+            if i > 0 {
+                let duration = tw.timer.duration();
+                if sequence.advance(&mut commands, entity, &mut tw, revealed(&reveal), children) {
+                    break;
+                }
+
+                // A speed command changes the duration of the remaining ticks.
+                if tw.timer.duration() != duration {
+                    let remaining = duration * (ticks - i) + tw.timer.elapsed();
+                    let count = (remaining.as_nanos() / tw.timer.duration().as_nanos()) as u32;
+                    let elapsed = remaining - tw.timer.duration() * count;
+                    tw.timer.set_elapsed(elapsed);
+                    ticks = i + count;
+                    if count == 0 {
+                        break;
+                    }
+                }
+            }
+            i += 1;
+
+            let segmentation = segmentation.copied().unwrap_or_default();
+            let Some(unit) = next_unit(block, *mode, segmentation, reveal.0) else {
+                break;
+            };
+
+            if *mode == TypeWriterMode::Glyph
+                && let Some(human) = human.as_mut()
+                && let Some((typo, mistyped)) = typist.mistype(
+                    human,
+                    block,
+                    glyphs,
+                    unit.line_index,
+                    unit.range.start,
+                    reveal.0,
+                )
+            {
+                reveal.0 = mistyped.reveal;
+                commands.entity(mistyped.glyph).insert(Glyph(typo));
+                commands
+                    .entity(entity)
+                    .trigger(GlyphRevealed {
+                        glyph: Some(mistyped.glyph),
+                        text: mistyped.text.clone(),
+                        image: None,
+                    })
+                    .insert((mistyped, PauseTypeWriter::from_seconds(human.typo_delay())));
+                break;
+            }
+
+            reveal.0 += unit.graphemes;
+            if unit.range.is_empty() {
+                continue;
+            }
+
+            let mut glyphs = glyphs
+                .iter()
+                .flat_map(|glyph| glyph_query.get(glyph).map(|g| (glyph, g)).ok())
+                .filter(|(_, glyph)| {
+                    let glyph_start = glyph.0.byte_index;
+                    let glyph_end = glyph.0.byte_index + glyph.0.byte_length;
+                    glyph.0.line_index == unit.line_index
+                        && glyph_start < unit.range.end
+                        && glyph_end > unit.range.start
+                })
+                .map(|(entity, _)| entity);
+            let text = unit.text.to_string();
+
             match mode {
                 TypeWriterMode::Glyph => {
-                    let text = block
-                        .buffer()
-                        .lines
-                        .get(line_index)
-                        .map(|line| {
-                            &line.text()[reveal.0 - line_offset..reveal.0 - line_offset + 1]
-                        })
-                        // TODO: try again next frame instead?
-                        .ok_or("`ComputedTextBlock` buffer is empty")?;
-
-                    commands.entity(entity).trigger(GlyphRevealed {
-                        glyph: glyphs
-                            .iter()
-                            .flat_map(|glyph| glyph_query.get(glyph).map(|g| (glyph, g)).ok())
-                            .find_map(|(entity, glyph)| {
-                                (glyph.0.byte_index + glyph.0.byte_length
-                                    == reveal.0 - line_offset + 1)
-                                    .then_some(entity)
-                            }),
-                        text: text.to_string(),
-                    });
-
-                    reveal.0 += 1;
+                    let glyph = glyphs.next();
+                    let image = glyph
+                        .and_then(|glyph| image_glyphs.get(glyph).ok())
+                        .and_then(|span| text_images.get(span.0).ok())
+                        .map(|image| image.0.clone());
+                    commands
+                        .entity(entity)
+                        .trigger(GlyphRevealed { glyph, text, image });
                 }
                 TypeWriterMode::Word => {
-                    let text = block
-                        .buffer()
-                        .lines
-                        .get(line_index)
-                        .map(|line| line.text())
-                        // TODO: try again next frame instead?
-                        .ok_or("`ComputedTextBlock` buffer is empty")?;
-
-                    let start = reveal.0 - line_offset;
-                    let word_slice = &text[start..];
-                    let word_end_offset = word_slice
-                        .chars()
-                        .position(|c| c.is_whitespace())
-                        .unwrap_or(word_slice.len());
-
-                    let end = start + word_end_offset;
-                    let mut last_char = text[start..start + 1].chars().next().unwrap();
-
-                    match text[start..].chars().position(|char| {
-                        let next_word = last_char.is_whitespace() && !char.is_whitespace();
-                        last_char = char;
-                        next_word
-                    }) {
-                        Some(next_space) => reveal.0 += next_space,
-                        None => reveal.0 = accum,
-                    }
-
                     debug_assert!(
-                        !text[start..end].chars().any(char::is_whitespace),
-                        "revealed word contains whitespace: `{}`",
-                        &text[start..end]
+                        !text.chars().any(char::is_whitespace),
+                        "revealed word contains whitespace: `{text}`",
                     );
-
                     commands.entity(entity).trigger(WordRevealed {
-                        glyphs: glyphs
-                            .iter()
-                            .flat_map(|glyph| glyph_query.get(glyph).map(|g| (glyph, g)).ok())
-                            .filter_map(|(entity, glyph)| {
-                                let glyph_start = glyph.0.byte_index;
-                                let glyph_end = glyph.0.byte_index + glyph.0.byte_length;
-                                (glyph_start < end && glyph_end > start).then_some(entity)
-                            })
-                            .collect(),
-                        text: text[start..end].to_string(),
+                        glyphs: glyphs.collect(),
+                        text,
+                    });
+                }
+                TypeWriterMode::Line => {
+                    commands.entity(entity).trigger(LineRevealed {
+                        glyphs: glyphs.collect(),
+                        text,
+                    });
+                }
+                TypeWriterMode::Sentence => {
+                    commands.entity(entity).trigger(SentenceRevealed {
+                        glyphs: glyphs.collect(),
+                        text,
                     });
                 }
             }
+
+            let interval = tw.timer.duration().as_secs_f32();
+            let mut delay = 0.;
+            if let Some(pacing) = pacing {
+                let no_pacing = children
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|child| no_pacing.get(*child).ok())
+                    .map(|range| range.0.clone())
+                    .collect::<Vec<_>>();
+                // An explicit pause or wait replaces the pace.
+                let next = graphemes(block).nth(reveal.0);
+                if let Some(pace) = unit_pace(pacing, &unit, next, &no_pacing)
+                    && !sequence.holds(&tw, revealed_bytes(block, reveal.0), children)
+                {
+                    delay += pace.delay(interval);
+                }
+            }
+            // The end of the text is not delayed.
+            if let Some(human) = human.as_mut()
+                && graphemes(block).nth(reveal.0).is_some()
+            {
+                delay += human.jitter(interval);
+            }
+
+            if delay > 0. {
+                commands
+                    .entity(entity)
+                    .insert(PauseTypeWriter::from_seconds(delay));
+                break;
+            } else if delay < 0. {
+                let elapsed = tw.timer.elapsed() + Duration::from_secs_f32(-delay);
+                let duration = tw.timer.duration();
+                tw.timer.set_elapsed(elapsed.min(duration));
+            }
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+    use bevy::text::TextBounds;
+    use bevy::time::TimeUpdateStrategy;
+    use unicode_segmentation::UnicodeSegmentation;
+
+    use crate::parser::PrettyTextParser;
+    use crate::test::{prepare_app, roots};
+    use crate::type_writer::hierarchy::PrettyCallbackAppExt;
+
+    use super::human::HumanTyping;
+    use super::pacing::{Pace, TypeWriterPacing};
+    use super::timeline::TypeWriterTimeline;
+    use super::{
+        GlyphErased, GlyphRevealed, LineRevealed, PauseTypeWriter, Reveal, SentenceRevealed,
+        TypeWriter, TypeWriterEvent, TypeWriterMode, TypeWriterSignal, TypeWriterWaiting,
+        WaitingForSignal, WordSegmentation, next_word,
+    };
+
+    #[derive(Default, Resource)]
+    struct Revealed(Vec<String>);
+
+    // An app that advances 100ms every frame and collects the [`Revealed`] text.
+    fn typing_app() -> App {
+        let mut app = prepare_app();
+        app.init_resource::<Revealed>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+        app
+    }
+
+    // Spawns a type writer that pushes every revealed grapheme to [`Revealed`].
+    fn spawn_revealing(app: &mut App, bundle: impl Bundle) -> EntityWorldMut<'_> {
+        let mut entity = app.world_mut().spawn(bundle);
+        entity.observe(
+            |trigger: Trigger<GlyphRevealed>, mut revealed: ResMut<Revealed>| {
+                revealed.0.push(trigger.text.clone());
+            },
+        );
+        entity
+    }
+
+    // Updates `app` and takes the text revealed in the frame.
+    fn frame(app: &mut App) -> String {
+        app.update();
+        std::mem::take(&mut app.world_mut().resource_mut::<Revealed>().0).join("")
+    }
+
+    #[test]
+    fn reveal_graphemes() {
+        let texts = roots().map(|(text, _)| text).chain(["a̐é 👨‍👩‍👧\n漢字"]);
+        for text in texts {
+            let mut app = typing_app();
+            spawn_revealing(&mut app, (TypeWriter::new(10.), Text2d::new(text)));
+
+            let graphemes = text
+                .lines()
+                .flat_map(|line| line.graphemes(true))
+                .collect::<Vec<_>>();
+            for _ in 0..graphemes.len() + 2 {
+                app.update();
+            }
+
+            assert_eq!(app.world().resource::<Revealed>().0, graphemes);
+        }
+    }
+
+    fn words(text: &str, segmentation: WordSegmentation) -> Vec<&str> {
+        let mut words = Vec::new();
+        let mut offset = 0;
+        while offset < text.len() {
+            let (word, consumed) = next_word(&text[offset..], segmentation);
+            words.push(&text[offset + word.start..offset + word.end]);
+            offset += consumed;
+        }
+        words
+    }
+
+    #[test]
+    fn segment_words() {
+        assert_eq!(
+            words("  \"Hello,\" she said... don't", WordSegmentation::Unicode),
+            ["\"Hello,\"", "she", "said...", "don't"]
+        );
+        assert_eq!(
+            words("旅人さん、こんにちは！", WordSegmentation::Unicode),
+            ["旅", "人", "さ", "ん、", "こ", "ん", "に", "ち", "は！"]
+        );
+        assert_eq!(
+            words("カタカナ語", WordSegmentation::Unicode),
+            ["カタカナ", "語"]
+        );
+        assert_eq!(
+            words("カタカナ語", WordSegmentation::Ideographic),
+            ["カ", "タ", "カ", "ナ", "語"]
+        );
+    }
+
+    fn reveal_units(mode: TypeWriterMode, text: &str, width: f32) -> Vec<(String, usize)> {
+        #[derive(Default, Resource)]
+        struct Units(Vec<(String, usize)>);
+
+        let mut app = typing_app();
+        app.init_resource::<Units>();
+
+        app.world_mut()
+            .spawn((
+                TypeWriter::new(10.),
+                mode,
+                Text2d::new(text),
+                TextBounds::new_horizontal(width),
+            ))
+            .observe(|trigger: Trigger<LineRevealed>, mut units: ResMut<Units>| {
+                units.0.push((trigger.text.clone(), trigger.glyphs.len()));
+            })
+            .observe(
+                |trigger: Trigger<SentenceRevealed>, mut units: ResMut<Units>| {
+                    units.0.push((trigger.text.clone(), trigger.glyphs.len()));
+                },
+            );
+        for _ in 0..10 {
+            app.update();
+        }
+
+        app.world_mut().remove_resource::<Units>().unwrap().0
+    }
+
+    #[test]
+    fn reveal_lines() {
+        // Wraps after each word.
+        assert_eq!(
+            reveal_units(TypeWriterMode::Line, "aaa bbb ccc\ndd", 60.),
+            [
+                ("aaa".into(), 3),
+                ("bbb".into(), 3),
+                ("ccc".into(), 3),
+                ("dd".into(), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn reveal_sentences() {
+        assert_eq!(
+            reveal_units(
+                TypeWriterMode::Sentence,
+                "Hi there. Are you ok? Yes!",
+                1000.
+            ),
+            [
+                ("Hi there.".into(), 9),
+                ("Are you ok?".into(), 11),
+                ("Yes!".into(), 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn reveal_many_per_frame() {
+        let mut app = typing_app();
+
+        // 4 graphemes every frame, and the first grapheme is revealed immediately.
+        spawn_revealing(
+            &mut app,
+            (
+                TypeWriter::new(40.),
+                PrettyTextParser::bundle("ab{event}cdef[1]gh").unwrap(),
+            ),
+        )
+        .observe(
+            |trigger: Trigger<TypeWriterEvent>, mut revealed: ResMut<Revealed>| {
+                revealed.0.push(trigger.0.clone());
+            },
+        );
+
+        let frames = (0..5).map(|_| frame(&mut app)).collect::<Vec<_>>();
+
+        assert_eq!(frames, ["", "abeventcde", "f", "", ""]);
+    }
+
+    #[test]
+    fn speed_change_mid_frame() {
+        let mut app = typing_app();
+
+        // 4 graphemes every frame until the speed drops to 1 grapheme every 250ms.
+        spawn_revealing(
+            &mut app,
+            (
+                TypeWriter::new(40.),
+                PrettyTextParser::bundle("ab<0.1>cdefgh").unwrap(),
+            ),
+        );
+
+        let frames = (0..9).map(|_| frame(&mut app)).collect::<Vec<_>>();
+
+        // The 50ms left over after `b` carries into the slower pace.
+        assert_eq!(frames, ["", "ab", "", "c", "", "", "d", "", "e"]);
+    }
+
+    #[test]
+    fn wait_for_input() {
+        let mut app = typing_app();
+        let entity = spawn_revealing(
+            &mut app,
+            (
+                TypeWriter::new(40.),
+                PrettyTextParser::bundle("ab[wait]cd").unwrap(),
+            ),
+        )
+        .id();
+
+        let frame = |app: &mut App| {
+            let revealed = frame(app);
+            (
+                revealed,
+                app.world().get::<TypeWriterWaiting>(entity).is_some(),
+            )
+        };
+
+        frame(&mut app);
+        assert_eq!(frame(&mut app), (String::from("ab"), true));
+        assert_eq!(frame(&mut app), (String::new(), true));
+
+        app.world_mut()
+            .get_mut::<TypeWriter>(entity)
+            .unwrap()
+            .advance();
+        assert_eq!(frame(&mut app), (String::from("cd"), false));
+    }
+
+    #[test]
+    fn pacing() {
+        let mut app = typing_app();
+        app.update();
+
+        let entity = app
+            .world_mut()
+            .spawn((
+                TypeWriter::new(10.),
+                TypeWriterPacing::empty().with(",", Pace::Pause(0.5)),
+                TypeWriterTimeline::default(),
+                PrettyTextParser::bundle("a,b`,c`[no_pacing],[0.5]d").unwrap(),
+            ))
+            .id();
+
+        let mut reveals = Vec::new();
+        for _ in 0..17 {
+            app.update();
+            reveals.push(
+                app.world()
+                    .get::<Reveal>(entity)
+                    .map_or(usize::MAX, |r| r.0),
+            );
+        }
+        assert_eq!(reveals, [0, 1, 2, 2, 2, 2, 2, 3, 4, 5, 6, 6, 6, 6, 6, 6, 7]);
+
+        let duration = app
+            .world()
+            .get::<TypeWriterTimeline>(entity)
+            .unwrap()
+            .total_duration();
+        assert!((duration - 1.6).abs() < 1e-4, "{duration}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_speed_pace() {
+        let _ = TypeWriterPacing::empty().with(",", Pace::Speed(0.));
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_pause_pace() {
+        let _ = TypeWriterPacing::empty().with(",", Pace::Pause(-1.));
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_typo_pause() {
+        let _ = HumanTyping::new(0).with_typos(0.5, -1.);
+    }
+
+    #[test]
+    #[should_panic]
+    fn infinite_jitter() {
+        let _ = HumanTyping::new(0).with_jitter(f32::INFINITY);
+    }
+
+    #[test]
+    #[should_panic]
+    fn typo_chance_above_one() {
+        let _ = HumanTyping::new(0).with_typos(1.5, 0.3);
+    }
+
+    #[test]
+    fn human_typing() {
+        let run = |human: HumanTyping| {
+            let mut app = typing_app();
+            spawn_revealing(&mut app, (TypeWriter::new(10.), human, Text2d::new("sa"))).observe(
+                |trigger: Trigger<GlyphErased>, mut revealed: ResMut<Revealed>| {
+                    revealed.0.push(format!("-{}", trigger.text));
+                },
+            );
+
+            let mut reveals = Vec::new();
+            for _ in 0..40 {
+                app.update();
+                reveals.push(
+                    app.world_mut()
+                        .query::<&Reveal>()
+                        .iter(app.world())
+                        .next()
+                        .map(|reveal| reveal.0),
+                );
+            }
+            (app.world().resource::<Revealed>().0.clone(), reveals)
+        };
+
+        let (revealed, _) = run(HumanTyping::new(0).with_jitter(0.).with_typos(1., 0.5));
+        assert_eq!(revealed, ["a", "-a", "s", "s", "-s", "a"]);
+
+        let human = HumanTyping::new(7).with_jitter(0.5).with_typos(0.5, 0.2);
+        assert_eq!(run(human.clone()), run(human));
+
+        // Invalid fields that bypass the builder are ignored.
+        let mut human = HumanTyping::new(0).with_typos(1., 0.5);
+        human.typo_pause = f32::INFINITY;
+        let (revealed, _) = run(human);
+        assert_eq!(revealed, ["a", "-a", "s", "s", "-s", "a"]);
+
+        let mut human = HumanTyping::new(0);
+        human.jitter = f32::INFINITY;
+        human.typo_chance = f32::NAN;
+        let (revealed, _) = run(human);
+        assert_eq!(revealed, ["s", "a"]);
+    }
+
+    #[test]
+    fn wait_for_signal() {
+        let mut app = typing_app();
+        let entity = spawn_revealing(
+            &mut app,
+            (
+                TypeWriter::new(40.),
+                PrettyTextParser::bundle("a{wait:door}b{wait:door:0.15}c").unwrap(),
+            ),
+        )
+        .id();
+
+        let frame = |app: &mut App| {
+            let revealed = frame(app);
+            let signal = app
+                .world()
+                .get::<WaitingForSignal>(entity)
+                .map(|wait| wait.signal.clone());
+            (revealed, signal)
+        };
+        let door = || Some(String::from("door"));
+
+        frame(&mut app);
+        assert_eq!(frame(&mut app), (String::from("a"), door()));
+
+        // Other signals are ignored.
+        app.world_mut().trigger(TypeWriterSignal::new("window"));
+        assert_eq!(frame(&mut app), (String::new(), door()));
+
+        app.world_mut().trigger(TypeWriterSignal::new("door"));
+        assert_eq!(frame(&mut app), (String::from("b"), door()));
+
+        // Continues after the timeout.
+        assert_eq!(frame(&mut app), (String::new(), door()));
+        assert_eq!(frame(&mut app), (String::from("c"), None));
+    }
+
+    #[test]
+    fn remove_while_held() {
+        let mut app = typing_app();
+        let paused = spawn_revealing(
+            &mut app,
+            (
+                TypeWriter::new(40.),
+                PrettyTextParser::bundle("a[1]b").unwrap(),
+            ),
+        )
+        .id();
+        let waiting = spawn_revealing(
+            &mut app,
+            (
+                TypeWriter::new(40.),
+                PrettyTextParser::bundle("a{wait:door}b").unwrap(),
+            ),
+        )
+        .id();
+        for _ in 0..2 {
+            app.update();
+        }
+        assert!(app.world().get::<PauseTypeWriter>(paused).is_some());
+        assert!(app.world().get::<WaitingForSignal>(waiting).is_some());
+
+        for entity in [paused, waiting] {
+            app.world_mut().entity_mut(entity).remove::<TypeWriter>();
+        }
+        app.update();
+        assert!(app.world().get::<PauseTypeWriter>(paused).is_none());
+        assert!(app.world().get::<WaitingForSignal>(waiting).is_none());
+    }
+
+    #[test]
+    fn named_callback() {
+        #[derive(Default, Resource)]
+        struct Calls(usize);
+
+        let mut app = typing_app();
+        app.init_resource::<Calls>()
+            .register_pretty_callback("call", |mut calls: ResMut<Calls>| calls.0 += 1);
+
+        app.world_mut().spawn((
+            TypeWriter::new(40.),
+            PrettyTextParser::bundle("a{@call}b{@call}{@missing}").unwrap(),
+        ));
+        for _ in 0..3 {
+            app.update();
+        }
+
+        assert_eq!(app.world().resource::<Calls>().0, 2);
+    }
+}
@@ -0,0 +1,196 @@
+//! Automatic pauses after punctuation.
+//!
+//! A [`TypeWriterPacing`] on a [`TypeWriter`] entity holds the type writer
+//! after revealing punctuation, so that text reads naturally without placing
+//! a pause command after every comma.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::type_writer::*;
+//! # use pretty_text::type_writer::pacing::*;
+#![doc = include_str!("../../docs/pretty.txt")]
+//! #
+//! # let mut world = World::new();
+//! // Pauses after `,`, `.`, `!`, `?`, `…` and line breaks.
+//! world.spawn((
+//!     TypeWriter::new(30.0),
+//!     TypeWriterPacing::default(),
+//!     pretty!("Well, well. What have we here?"),
+//! ));
+//!
+//! // Custom pacing.
+//! world.spawn((
+//!     TypeWriter::new(30.0),
+//!     TypeWriterPacing::empty()
+//!         .with(".", Pace::Pause(0.5))
+//!         .with("-", Pace::Speed(0.25)),
+//!     pretty!("Wait - what."),
+//! ));
+//! ```
+//!
+//! A pace is applied after the last grapheme of a revealed unit, so in
+//! [`TypeWriterMode::Word`](super::TypeWriterMode::Word) the pause follows the
+//! word that ends with the punctuation.
+//!
+//! # Overrides
+//!
+//! An explicit pause or wait command, e.g. `"Hello.[2]"`, replaces the
+//! automatic pause at the same position.
+//!
+//! Spans with the `no_pacing` effect are revealed without automatic pauses:
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::type_writer::*;
+//! # use pretty_text::type_writer::pacing::*;
+#![doc = include_str!("../../docs/pretty.txt")]
+//! #
+//! # let mut world = World::new();
+//! world.spawn((
+//!     TypeWriter::new(30.0),
+//!     TypeWriterPacing::default(),
+//!     pretty!("Version `1.2.3, build 4`[no_pacing] is out."),
+//! ));
+//! ```
+
+use std::borrow::Cow;
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::dynamic_effects::DynamicEffect;
+
+/// Automatically pauses a [`TypeWriter`](super::TypeWriter) after graphemes.
+///
+/// See [`pacing`](crate::type_writer::pacing).
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+pub struct TypeWriterPacing(HashMap<Cow<'static, str>, Pace>);
+
+impl Default for TypeWriterPacing {
+    /// Pauses after `,`, `;`, `:`, `.`, `!`, `?`, `…` and line breaks.
+    fn default() -> Self {
+        Self::empty()
+            .with(",", Pace::Pause(0.15))
+            .with(";", Pace::Pause(0.15))
+            .with(":", Pace::Pause(0.15))
+            .with(".", Pace::Pause(0.3))
+            .with("!", Pace::Pause(0.3))
+            .with("?", Pace::Pause(0.3))
+            .with("…", Pace::Pause(0.3))
+            .with(Self::NEWLINE, Pace::Pause(0.3))
+    }
+}
+
+impl TypeWriterPacing {
+    /// Matches the end of a line that is followed by more text.
+    ///
+    /// Lines that are wrapped by the layout do not match.
+    pub const NEWLINE: &'static str = "\n";
+
+    /// Creates a pacing without any graphemes.
+    #[inline]
+    pub fn empty() -> Self {
+        Self(HashMap::default())
+    }
+
+    /// Applies `pace` after `grapheme`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pace` is not valid, see [`Pace`].
+    #[inline]
+    pub fn with(mut self, grapheme: impl Into<Cow<'static, str>>, pace: Pace) -> Self {
+        self.insert(grapheme, pace);
+        self
+    }
+
+    /// Applies `pace` after `grapheme`, replacing any previous pace.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pace` is not valid, see [`Pace`].
+    #[inline]
+    pub fn insert(&mut self, grapheme: impl Into<Cow<'static, str>>, pace: Pace) {
+        assert!(pace.is_valid(), "invalid pace: {pace:?}");
+        self.0.insert(grapheme.into(), pace);
+    }
+
+    /// Removes the pace of `grapheme`.
+    #[inline]
+    pub fn remove(&mut self, grapheme: &str) -> Option<Pace> {
+        self.0.remove(grapheme)
+    }
+
+    /// Retrieves the pace of `grapheme`.
+    #[inline]
+    pub fn get(&self, grapheme: &str) -> Option<Pace> {
+        self.0.get(grapheme).copied()
+    }
+
+    // Finds the pace after a unit that ends with `grapheme`.
+    pub(super) fn pace(&self, grapheme: &str, line_end: bool) -> Option<Pace> {
+        self.get(grapheme)
+            .or_else(|| line_end.then(|| self.get(Self::NEWLINE)).flatten())
+    }
+}
+
+/// The delay applied by [`TypeWriterPacing`] after a grapheme.
+///
+/// A pause must be finite and not negative, and a speed must be finite and
+/// greater than zero.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum Pace {
+    /// Pause for a duration in seconds.
+    Pause(f32),
+
+    /// Multiply the speed of the next unit, e.g. `0.5` doubles the delay
+    /// before the next unit is revealed.
+    Speed(f32),
+}
+
+impl Pace {
+    fn is_valid(self) -> bool {
+        match self {
+            Self::Pause(duration) => duration.is_finite() && duration >= 0.,
+            Self::Speed(mult) => mult.is_finite() && mult > 0.,
+        }
+    }
+
+    // The delay in seconds added to `interval`, the time between two units.
+    pub(super) fn delay(self, interval: f32) -> f32 {
+        match self {
+            Self::Pause(duration) => duration,
+            Self::Speed(mult) => interval / mult - interval,
+        }
+    }
+}
+
+/// Reveals a span without the pauses of [`TypeWriterPacing`].
+///
+/// Inserted by the `no_pacing` effect, e.g. ``"`1.2.3`[no_pacing]"``.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component, Reflect)]
+pub struct NoPacing;
+
+impl NoPacing {
+    /// The tag of the `no_pacing` effect.
+    pub const TAG: &'static str = "no_pacing";
+}
+
+impl DynamicEffect for NoPacing {
+    fn insert_from_args(
+        &self,
+        args: &[Cow<'static, str>],
+        entity: &mut EntityCommands,
+    ) -> Result<()> {
+        if !args.is_empty() {
+            return Err(format!("expected 0 arguments for `no_pacing`, got {}", args.len()).into());
+        }
+
+        entity.insert(NoPacing);
+        Ok(())
+    }
+
+    fn fields(&self) -> Option<&'static [&'static str]> {
+        Some(&[])
+    }
+}
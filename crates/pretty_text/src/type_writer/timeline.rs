@@ -0,0 +1,586 @@
+//! Precomputes the timing of a [`TypeWriter`] sequence.
+//!
+//! A [`TypeWriterTimeline`] is compiled from the text hierarchy of a
+//! [`TypeWriter`], combining its text with the
+//! [hierarchy components](super::hierarchy) into a list of timed
+//! [`TimelineEntry`]s.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::type_writer::*;
+//! # use pretty_text::type_writer::timeline::*;
+#![doc = include_str!("../../docs/pretty.txt")]
+//! #
+//! # let mut world = World::new();
+//! world.spawn((
+//!     TypeWriter::new(30.0),
+//!     TypeWriterTimeline::default(),
+//!     pretty!("The door creaks...[1] and {door_open}opens."),
+//! ));
+//!
+//! fn progress_bar(timelines: Query<&TypeWriterTimeline>) {
+//!     for timeline in timelines.iter() {
+//!         println!(
+//!             "{:.0}% of {:.1}s",
+//!             timeline.progress() * 100.0,
+//!             timeline.total_duration()
+//!         );
+//!     }
+//! }
+//!
+//! fn scrub(mut timelines: Query<&mut TypeWriterTimeline>) {
+//!     for mut timeline in timelines.iter_mut() {
+//!         timeline.seek(0.5);
+//!     }
+//! }
+//! ```
+//!
+//! # Seeking
+//!
+//! [`TypeWriterTimeline::seek`] and [`TypeWriterTimeline::skip_to_next_pause`]
+//! move the [`TypeWriter`] to a point in time. The [`Reveal`], speed and pause
+//! of the type writer are set as if it had played up to that point.
+//!
+//! Events and callbacks that are passed when seeking forward are fired or
+//! suppressed according to [`SeekEvents`]. Events and callbacks that are
+//! passed when seeking backward will fire again when they are reached.
+//!
+//! Seeking has no effect once the [`TypeWriter`] is finished. A type writer that is
+//! waiting for [input](super::TypeWriterWaiting) or a [signal](super::WaitingForSignal)
+//! continues after a seek.
+//!
+//! Waiting takes no time in the timeline, and the elapsed time stops while the
+//! type writer waits.
+//!
+//! The pauses of [`TypeWriterPacing`] are included in the timeline.
+//!
+//! The timeline assumes that exactly one unit of text is revealed every tick of
+//! the type writer, so the actual timing may differ from the timeline by up to
+//! a frame.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::text::ComputedTextBlock;
+
+use super::hierarchy::{
+    TypeWriterCallback, TypeWriterCommand, TypeWriterEvent, TypeWriterNamedCallback,
+};
+use super::pacing::{NoPacing, TypeWriterPacing};
+use super::{
+    ByteRange, PaceChild, PauseTypeWriter, Reveal, Sequence, TypeWriter, TypeWriterMode,
+    TypeWriterWaiting, WaitingForSignal, WordSegmentation, byte_offset, graphemes, holds,
+    unit_from, unit_pace,
+};
+use crate::glyph::Glyphs;
+
+/// The timing of a [`TypeWriter`] sequence.
+///
+/// See [`timeline`](super::timeline).
+#[derive(Debug, Default, Clone, Component, Reflect)]
+pub struct TypeWriterTimeline {
+    entries: Vec<TimelineEntry>,
+    elapsed: f32,
+    seek: Option<f32>,
+
+    /// Configures how events and callbacks are handled when seeking forward.
+    pub seek_events: SeekEvents,
+}
+
+impl TypeWriterTimeline {
+    /// The entries of the timeline, ordered by time.
+    pub fn entries(&self) -> &[TimelineEntry] {
+        &self.entries
+    }
+
+    /// The duration of the sequence in seconds.
+    pub fn total_duration(&self) -> f32 {
+        self.entries
+            .last()
+            .map(TimelineEntry::end)
+            .unwrap_or_default()
+    }
+
+    /// The time in seconds since the sequence started.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// The fraction of the sequence that has played, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        let total = self.total_duration();
+        if total > 0. {
+            (self.elapsed / total).clamp(0., 1.)
+        } else {
+            1.
+        }
+    }
+
+    /// Move the sequence to `seconds`.
+    ///
+    /// The seek is applied in the next [`PostUpdate`].
+    pub fn seek(&mut self, seconds: f32) {
+        self.seek = Some(seconds.clamp(0., self.total_duration()));
+    }
+
+    /// Move the sequence to the start of the next pause command, or to the end
+    /// if there are no more pause commands.
+    ///
+    /// The pauses of [`TypeWriterPacing`] are skipped over.
+    pub fn skip_to_next_pause(&mut self) {
+        let elapsed = self.seek.unwrap_or(self.elapsed);
+        let next = self
+            .entries
+            .iter()
+            .find(|entry| {
+                matches!(entry.kind, TimelineEntryKind::Pause(_))
+                    && entry.source.is_some()
+                    && entry.time > elapsed
+            })
+            .map(|entry| entry.time)
+            .unwrap_or(self.total_duration());
+        self.seek(next);
+    }
+}
+
+/// Configures how events and callbacks are handled when a [`TypeWriterTimeline`] seeks
+/// forward.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum SeekEvents {
+    /// Events and callbacks that are passed are not fired.
+    #[default]
+    Suppress,
+
+    /// Events and callbacks that are passed are fired in order.
+    Fire,
+}
+
+/// A timed entry in a [`TypeWriterTimeline`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct TimelineEntry {
+    /// Time in seconds from the start of the sequence.
+    pub time: f32,
+
+    /// The [`Reveal`] after the entry.
+    pub reveal: usize,
+
+    /// The child of the text hierarchy that produced the entry.
+    ///
+    /// `source` is `None` for [`TimelineEntryKind::Reveal`] and the pauses of
+    /// [`TypeWriterPacing`].
+    pub source: Option<Entity>,
+
+    /// What happens at `time`.
+    pub kind: TimelineEntryKind,
+}
+
+impl TimelineEntry {
+    fn end(&self) -> f32 {
+        match self.kind {
+            TimelineEntryKind::Pause(duration) => self.time + duration,
+            _ => self.time,
+        }
+    }
+}
+
+/// What happens at a [`TimelineEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum TimelineEntryKind {
+    /// A unit of text is revealed.
+    Reveal,
+
+    /// The [`TypeWriter`] pauses for a duration in seconds.
+    Pause(f32),
+
+    /// A multiplier is applied to the base speed.
+    Speed(f32),
+
+    /// The [`TypeWriter`] waits for input or a signal.
+    Wait,
+
+    /// A [`TypeWriterEvent`] is triggered.
+    Event,
+
+    /// A [`TypeWriterCallback`] or [`TypeWriterNamedCallback`] is run.
+    Callback,
+}
+
+// A child of the text hierarchy, in the terms of the timeline.
+enum Child {
+    Span(usize),
+    Command(TypeWriterCommand),
+    Event,
+    Callback,
+}
+
+fn compile(
+    block: &ComputedTextBlock,
+    mode: TypeWriterMode,
+    segmentation: WordSegmentation,
+    speed: f32,
+    pacing: Option<&TypeWriterPacing>,
+    no_pacing: &[Range<usize>],
+    children: &[(Entity, Child)],
+) -> Vec<TimelineEntry> {
+    let mut entries = Vec::new();
+    let mut time = 0.;
+    let mut interval = 1. / speed;
+    let mut reveal = 0;
+    let mut children = children.iter().peekable();
+    // Indexed once, each unit continues from the graphemes revealed so far.
+    let graphemes = graphemes(block).collect::<Vec<_>>();
+    let revealed_bytes = |reveal: usize| byte_offset(block, graphemes.get(reveal).copied());
+
+    loop {
+        // Nothing is reached before the first grapheme is revealed.
+        let revealed = (reveal != 0).then(|| revealed_bytes(reveal));
+        while let Some((entity, child)) = children.peek() {
+            let kind = match child {
+                Child::Span(end) => {
+                    if revealed.is_none_or(|revealed| *end > revealed) {
+                        break;
+                    }
+                    children.next();
+                    continue;
+                }
+                Child::Command(TypeWriterCommand::Pause(duration)) => {
+                    TimelineEntryKind::Pause(*duration)
+                }
+                Child::Command(TypeWriterCommand::Speed(mult)) => {
+                    interval = 1. / speed / mult;
+                    TimelineEntryKind::Speed(*mult)
+                }
+                Child::Command(
+                    TypeWriterCommand::WaitForInput | TypeWriterCommand::WaitFor { .. },
+                ) => TimelineEntryKind::Wait,
+                Child::Event => TimelineEntryKind::Event,
+                Child::Callback => TimelineEntryKind::Callback,
+            };
+
+            let entry = TimelineEntry {
+                time,
+                reveal,
+                source: Some(*entity),
+                kind,
+            };
+            time = entry.end();
+            entries.push(entry);
+            children.next();
+        }
+
+        let remaining = graphemes.get(reveal..).unwrap_or_default().iter().copied();
+        let Some(unit) = unit_from(block, mode, segmentation, remaining) else {
+            break;
+        };
+        // The first unit is revealed immediately.
+        if reveal != 0 {
+            time += interval;
+        }
+        reveal += unit.graphemes;
+        entries.push(TimelineEntry {
+            time,
+            reveal,
+            source: None,
+            kind: TimelineEntryKind::Reveal,
+        });
+
+        let next = graphemes.get(reveal).copied();
+        let Some(pace) = pacing.and_then(|pacing| unit_pace(pacing, &unit, next, no_pacing)) else {
+            continue;
+        };
+        // An explicit pause or wait replaces the pace.
+        let pending = children.clone().map(|(_, child)| match child {
+            Child::Span(end) => PaceChild::Span(*end),
+            Child::Command(command) => PaceChild::Command(command),
+            Child::Event | Child::Callback => PaceChild::Other,
+        });
+        if holds(revealed_bytes(reveal), pending) {
+            continue;
+        }
+
+        let delay = pace.delay(interval);
+        if delay > 0. {
+            let entry = TimelineEntry {
+                time,
+                reveal,
+                source: None,
+                kind: TimelineEntryKind::Pause(delay),
+            };
+            time = entry.end();
+            entries.push(entry);
+        } else {
+            time += delay;
+        }
+    }
+
+    entries
+}
+
+pub(super) fn compile_timelines(
+    mut timelines: Query<
+        (
+            &mut TypeWriterTimeline,
+            &TypeWriter,
+            &ComputedTextBlock,
+            &TypeWriterMode,
+            Option<&WordSegmentation>,
+            Option<&TypeWriterPacing>,
+            Option<&Children>,
+        ),
+        Or<(
+            Changed<Glyphs>,
+            Added<TypeWriterTimeline>,
+            Changed<TypeWriterMode>,
+            Changed<WordSegmentation>,
+            Changed<TypeWriterPacing>,
+        )>,
+    >,
+    spans: Query<(&ByteRange, Has<NoPacing>), With<TextSpan>>,
+    effects: Query<&TypeWriterCommand>,
+    events: Query<(), With<TypeWriterEvent>>,
+    callbacks: Query<(), Or<(With<TypeWriterCallback>, With<TypeWriterNamedCallback>)>>,
+) {
+    for (mut timeline, tw, block, mode, segmentation, pacing, children) in timelines.iter_mut() {
+        let no_pacing = children
+            .into_iter()
+            .flatten()
+            .filter_map(|child| spans.get(*child).ok())
+            .filter(|(_, no_pacing)| *no_pacing)
+            .map(|(range, _)| range.0.clone())
+            .collect::<Vec<_>>();
+        let children = children
+            .into_iter()
+            .flatten()
+            .filter_map(|child| {
+                let kind = if let Ok((range, _)) = spans.get(*child) {
+                    Child::Span(range.0.end)
+                } else if let Ok(effect) = effects.get(*child) {
+                    Child::Command(effect.clone())
+                } else if events.contains(*child) {
+                    Child::Event
+                } else if callbacks.contains(*child) {
+                    Child::Callback
+                } else {
+                    return None;
+                };
+                Some((*child, kind))
+            })
+            .collect::<Vec<_>>();
+
+        timeline.entries = compile(
+            block,
+            *mode,
+            segmentation.copied().unwrap_or_default(),
+            tw.speed,
+            pacing,
+            &no_pacing,
+            &children,
+        );
+    }
+}
+
+pub(super) fn seek_timelines(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut timelines: Query<(
+        Entity,
+        &mut TypeWriterTimeline,
+        Option<(&mut TypeWriter, &mut Reveal)>,
+        Has<PauseTypeWriter>,
+        Has<TypeWriterWaiting>,
+        Has<WaitingForSignal>,
+    )>,
+    mut sequence: Sequence,
+) {
+    for (entity, mut timeline, tw, has_pause, waiting, signal) in timelines.iter_mut() {
+        let Some((mut tw, mut reveal)) = tw else {
+            timeline.elapsed = timeline.total_duration();
+            continue;
+        };
+
+        let Some(target) = timeline.seek.take() else {
+            if !waiting && !signal {
+                timeline.elapsed =
+                    (timeline.elapsed + time.delta_secs()).min(timeline.total_duration());
+            }
+            continue;
+        };
+        timeline.elapsed = target;
+        if waiting || signal {
+            commands
+                .entity(entity)
+                .remove::<(TypeWriterWaiting, WaitingForSignal)>();
+        }
+
+        let reached = |entry: &&TimelineEntry| entry.time <= target;
+        reveal.0 = timeline
+            .entries
+            .iter()
+            .filter(reached)
+            .map(|entry| entry.reveal)
+            .max()
+            .unwrap_or_default();
+
+        for entry in timeline.entries.iter() {
+            let Some(source) = entry.source else {
+                continue;
+            };
+
+            let processed = tw.processed_children.contains(&source);
+            if !reached(&entry) {
+                if processed {
+                    tw.processed_children.retain(|child| *child != source);
+                }
+                continue;
+            } else if processed {
+                continue;
+            }
+
+            tw.processed_children.push(source);
+            if timeline.seek_events == SeekEvents::Suppress {
+                continue;
+            }
+            if !sequence.fire_event(&mut commands, entity, source) {
+                sequence.run_callback(&mut commands, source);
+            }
+        }
+
+        let mult = timeline
+            .entries
+            .iter()
+            .filter(reached)
+            .filter_map(|entry| match entry.kind {
+                TimelineEntryKind::Speed(mult) => Some(mult),
+                _ => None,
+            })
+            .next_back()
+            .unwrap_or(1.);
+        let duration = Duration::from_secs_f32(1. / tw.speed / mult);
+        tw.timer.set_duration(duration);
+
+        // The timer runs from the last reveal, and stops while paused.
+        let mut start = None;
+        let mut paused = None;
+        for entry in timeline.entries.iter().filter(reached) {
+            match entry.kind {
+                TimelineEntryKind::Reveal => start = Some(entry.time),
+                TimelineEntryKind::Pause(duration) => {
+                    start = start.map(|_| entry.end());
+                    paused = (entry.end() > target).then(|| {
+                        let mut pause = PauseTypeWriter::from_seconds(duration);
+                        pause
+                            .0
+                            .set_elapsed(Duration::from_secs_f32(target - entry.time));
+                        pause
+                    });
+                }
+                _ => {}
+            }
+        }
+        tw.timer.set_elapsed(match start {
+            Some(start) => Duration::from_secs_f32((target - start).max(0.)).min(duration),
+            // The first unit is revealed immediately.
+            None => duration,
+        });
+
+        match paused {
+            Some(pause) => {
+                commands.entity(entity).insert(pause);
+            }
+            None if has_pause => {
+                commands.entity(entity).remove::<PauseTypeWriter>();
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+    use bevy::time::TimeUpdateStrategy;
+
+    use crate::parser::PrettyTextParser;
+    use crate::test::prepare_app;
+    use crate::type_writer::hierarchy::TypeWriterEvent;
+    use crate::type_writer::{PauseTypeWriter, Reveal, TypeWriter};
+
+    use super::{SeekEvents, TimelineEntryKind, TypeWriterTimeline};
+
+    #[derive(Default, Resource)]
+    struct Events(usize);
+
+    fn seek(app: &mut App, entity: Entity, f: impl FnOnce(&mut TypeWriterTimeline)) -> usize {
+        f(&mut app
+            .world_mut()
+            .get_mut::<TypeWriterTimeline>(entity)
+            .unwrap());
+        app.update();
+        app.world().get::<Reveal>(entity).unwrap().0
+    }
+
+    #[test]
+    fn timeline() {
+        let mut app = prepare_app();
+        app.init_resource::<Events>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(1)));
+
+        let entity = app
+            .world_mut()
+            .spawn((
+                TypeWriter::new(10.),
+                TypeWriterTimeline {
+                    seek_events: SeekEvents::Fire,
+                    ..Default::default()
+                },
+                PrettyTextParser::bundle("ab{event}[1]cd").unwrap(),
+            ))
+            .observe(|_: Trigger<TypeWriterEvent>, mut events: ResMut<Events>| {
+                events.0 += 1;
+            })
+            .id();
+        app.update();
+        app.update();
+
+        let timeline = app.world().get::<TypeWriterTimeline>(entity).unwrap();
+        let entries = timeline
+            .entries()
+            .iter()
+            .map(|entry| (entry.kind, entry.reveal))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                (TimelineEntryKind::Reveal, 1),
+                (TimelineEntryKind::Reveal, 2),
+                (TimelineEntryKind::Event, 2),
+                (TimelineEntryKind::Pause(1.), 2),
+                (TimelineEntryKind::Reveal, 3),
+                (TimelineEntryKind::Reveal, 4),
+            ]
+        );
+        assert!((timeline.total_duration() - 1.3).abs() < 1e-4);
+
+        // Seeks to the start of the pause, firing the event.
+        assert_eq!(seek(&mut app, entity, |t| t.skip_to_next_pause()), 2);
+        assert!(app.world().get::<PauseTypeWriter>(entity).is_some());
+        assert_eq!(app.world().resource::<Events>().0, 1);
+
+        assert_eq!(seek(&mut app, entity, |t| t.seek(0.)), 1);
+        assert!(app.world().get::<PauseTypeWriter>(entity).is_none());
+        assert_eq!(app.world().resource::<Events>().0, 1);
+
+        // The event is fired again.
+        assert_eq!(seek(&mut app, entity, |t| t.seek(1.25)), 3);
+        assert_eq!(app.world().resource::<Events>().0, 2);
+        let progress = app
+            .world()
+            .get::<TypeWriterTimeline>(entity)
+            .unwrap()
+            .progress();
+        assert!((progress - 1.25 / 1.3).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,437 @@
+//! Validates [parsed](crate::parser) text against the registered
+//! [effects](crate::dynamic_effects), [materials](crate::material), and
+//! [styles](crate::style).
+//!
+//! The [`PrettyTextParser`] only checks that the syntax is valid. Unregistered
+//! modifiers are otherwise reported as errors when the text is spawned.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::parser::*;
+//! # use pretty_text::validation::*;
+//! #
+//! # let mut world = World::new();
+//! # world.init_resource::<pretty_text::dynamic_effects::DynEffectRegistry>();
+//! # world.init_resource::<pretty_text::material::DynMaterialRegistry>();
+//! # world.init_resource::<pretty_text::style::PrettyStyleRegistry>();
+//! # let world = &world;
+//! // Parse and validate in one step.
+//! let err = PrettyTextParser::validate("`my text`[wabe]", world).unwrap_err();
+//! assert_eq!(
+//!     err.0,
+//!     vec![ValidationError::UnregisteredEffect("wabe".into())],
+//! );
+//!
+//! // Or validate spans that are already parsed.
+//! let spans = PrettyTextParser::spans("`my text`[!bleu]").unwrap();
+//! let err = ValidationContext::new(world).validate(&spans).unwrap_err();
+//! assert_eq!(
+//!     err.0,
+//!     vec![ValidationError::UnregisteredStyle("bleu".into())],
+//! );
+//! ```
+//!
+//! [`PrettyTextParser`]: crate::parser::PrettyTextParser
+
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+
+use crate::dynamic_effects::{DynEffectRegistry, PrettyTextEffect};
+use crate::material::DynMaterialRegistry;
+use crate::parser::{
+    Modifier, Modifiers, PrettyTextParseError, PrettyTextSpans, Span, TextSpanBundle,
+};
+use crate::style::{PrettyStyleRegistry, SpanStyle};
+
+/// Validates [`PrettyTextSpans`] against the effect, material, and style registries.
+///
+/// See [`validation`](crate::validation).
+#[derive(Debug, Clone, Copy)]
+pub struct ValidationContext<'a> {
+    effects: &'a DynEffectRegistry,
+    materials: &'a DynMaterialRegistry,
+    styles: &'a PrettyStyleRegistry,
+}
+
+impl<'a> ValidationContext<'a> {
+    /// Creates a validation context from the registries in `world`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `world` does not contain the [`DynEffectRegistry`],
+    /// [`DynMaterialRegistry`], and [`PrettyStyleRegistry`] resources.
+    #[inline]
+    pub fn new(world: &'a World) -> Self {
+        Self::from_registries(
+            world.resource::<DynEffectRegistry>(),
+            world.resource::<DynMaterialRegistry>(),
+            world.resource::<PrettyStyleRegistry>(),
+        )
+    }
+
+    /// Creates a validation context from registries, useful when validating in
+    /// a system.
+    #[inline]
+    pub fn from_registries(
+        effects: &'a DynEffectRegistry,
+        materials: &'a DynMaterialRegistry,
+        styles: &'a PrettyStyleRegistry,
+    ) -> Self {
+        Self {
+            effects,
+            materials,
+            styles,
+        }
+    }
+
+    /// Validate every [`Modifier`] in `spans`.
+    ///
+    /// Returns all of the problems found.
+    pub fn validate(&self, spans: &PrettyTextSpans) -> Result<(), ValidationErrors> {
+        let mut errors = Vec::new();
+        let mut materials = Vec::new();
+        for bundle in spans.0.iter() {
+            self.validate_bundle(bundle, &mut materials, &mut errors);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors(errors))
+        }
+    }
+
+    fn validate_bundle(
+        &self,
+        bundle: &TextSpanBundle,
+        parent_materials: &mut Vec<Cow<'static, str>>,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let (span, mods) = match bundle {
+            TextSpanBundle::Span { span, mods, .. } => (span, mods),
+            TextSpanBundle::Conditional {
+                then, otherwise, ..
+            } => {
+                for bundle in then.iter().chain(otherwise.iter()) {
+                    self.validate_bundle(bundle, parent_materials, errors);
+                }
+                return;
+            }
+            TextSpanBundle::Plural { branches, .. } => {
+                for bundle in branches.iter().flatten() {
+                    self.validate_bundle(bundle, parent_materials, errors);
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        let len = parent_materials.len();
+        self.validate_mods(mods, parent_materials, errors);
+
+        match span {
+            Span::Text(_) | Span::Variable(_) | Span::Image(_) => {
+                if let [first, second, ..] = parent_materials.as_slice() {
+                    let error = ValidationError::MultipleMaterials {
+                        first: first.clone(),
+                        second: second.clone(),
+                    };
+                    if !errors.contains(&error) {
+                        errors.push(error);
+                    }
+                }
+            }
+            Span::Bundles(bundles) => {
+                for bundle in bundles.iter() {
+                    self.validate_bundle(bundle, parent_materials, errors);
+                }
+            }
+        }
+
+        parent_materials.truncate(len);
+    }
+
+    fn validate_mods(
+        &self,
+        mods: &Modifiers,
+        materials: &mut Vec<Cow<'static, str>>,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        for modifier in mods.0.iter() {
+            match modifier {
+                Modifier::Effect(effect) => {
+                    if let Some(material) = self.materials.get(effect.tag.as_ref()) {
+                        materials.push(effect.tag.clone());
+                        validate_args(effect, material.fields(), errors);
+                    } else if let Some(handler) = self.effects.get(effect.tag.as_ref()) {
+                        validate_args(effect, handler.fields(), errors);
+                    } else {
+                        errors.push(ValidationError::UnregisteredEffect(effect.tag.clone()));
+                    }
+                }
+                Modifier::Style(SpanStyle::Style(style)) => {
+                    self.validate_style(style, errors);
+                }
+                Modifier::Style(SpanStyle::StyleSet(set)) => {
+                    for style in set.iter() {
+                        self.validate_style(style, errors);
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate_style(&self, style: &str, errors: &mut Vec<ValidationError>) {
+        if self.styles.get(style).is_none() {
+            errors.push(ValidationError::UnregisteredStyle(Cow::Owned(style.into())));
+        }
+    }
+}
+
+fn validate_args(
+    effect: &PrettyTextEffect,
+    fields: Option<&'static [&'static str]>,
+    errors: &mut Vec<ValidationError>,
+) {
+    let Some(fields) = fields else {
+        return;
+    };
+
+    if effect.args.len() > fields.len() {
+        errors.push(ValidationError::TooManyArguments {
+            tag: effect.tag.clone(),
+            expected: fields.len(),
+            found: effect.args.len(),
+        });
+    }
+
+    let positional = &fields[..effect.args.len().min(fields.len())];
+    for (i, arg) in effect.named_args.iter().enumerate() {
+        if !fields.contains(&arg.name.as_ref()) {
+            errors.push(ValidationError::UnknownField {
+                tag: effect.tag.clone(),
+                field: arg.name.clone(),
+            });
+        } else if positional.contains(&arg.name.as_ref())
+            || effect.named_args[..i]
+                .iter()
+                .any(|other| other.name == arg.name)
+        {
+            errors.push(ValidationError::DuplicateField {
+                tag: effect.tag.clone(),
+                field: arg.name.clone(),
+            });
+        }
+    }
+}
+
+/// A problem found by a [`ValidationContext`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The text could not be parsed.
+    Parse(PrettyTextParseError),
+
+    /// No effect or material is registered with this tag.
+    UnregisteredEffect(Cow<'static, str>),
+
+    /// No [`PrettyStyle`](crate::style::PrettyStyle) is registered with this tag.
+    UnregisteredStyle(Cow<'static, str>),
+
+    /// An effect or material received more arguments than it has fields.
+    TooManyArguments {
+        /// Tag of the effect or material.
+        tag: Cow<'static, str>,
+        /// The number of fields that can be constructed from arguments.
+        expected: usize,
+        /// The number of supplied arguments.
+        found: usize,
+    },
+
+    /// A named argument does not match any field of an effect or material.
+    UnknownField {
+        /// Tag of the effect or material.
+        tag: Cow<'static, str>,
+        /// Name of the unknown field.
+        field: Cow<'static, str>,
+    },
+
+    /// A named argument supplies a field that is already supplied, either
+    /// positionally or by another named argument.
+    DuplicateField {
+        /// Tag of the effect or material.
+        tag: Cow<'static, str>,
+        /// Name of the duplicated field.
+        field: Cow<'static, str>,
+    },
+
+    /// Multiple materials apply to a single span.
+    ///
+    /// Only one material can be rendered at a time, see [`material`](crate::material).
+    MultipleMaterials {
+        /// Tag of the first material.
+        first: Cow<'static, str>,
+        /// Tag of the second material.
+        second: Cow<'static, str>,
+    },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "{err}"),
+            Self::UnregisteredEffect(tag) => write!(f, "effect `{tag}` is not registered"),
+            Self::UnregisteredStyle(tag) => write!(f, "style `{tag}` not found"),
+            Self::TooManyArguments {
+                tag,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected at most {expected} arguments for `{tag}`, got {found}"
+            ),
+            Self::UnknownField { tag, field } => {
+                write!(f, "unknown field `{field}` for `{tag}`")
+            }
+            Self::DuplicateField { tag, field } => {
+                write!(f, "field `{field}` in `{tag}` is already supplied")
+            }
+            Self::MultipleMaterials { first, second } => write!(
+                f,
+                "registered multiple materials on a single span: `{first}` and `{second}`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// A collection of every [`ValidationError`] found in a text hierarchy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationErrors(pub Vec<ValidationError>);
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::dynamic_effects::{NamedArg, PrettyTextEffectAppExt};
+    use crate::parser::{Modifier, ParseErrorKind, PrettyTextParser, TextSpanBundle};
+    use crate::test::prepare_app_with;
+
+    use super::{ValidationContext, ValidationError, ValidationErrors};
+
+    #[derive(Default, Component)]
+    struct Effect;
+
+    impl crate::dynamic_effects::DynamicEffect for Effect {
+        fn insert_from_args(
+            &self,
+            _: &[std::borrow::Cow<'static, str>],
+            entity: &mut EntityCommands,
+        ) -> Result<()> {
+            entity.insert(Effect);
+            Ok(())
+        }
+
+        fn fields(&self) -> Option<&'static [&'static str]> {
+            Some(&["intensity"])
+        }
+    }
+
+    fn prepare_app() -> App {
+        let mut app = prepare_app_with(|app| {
+            app.register_pretty_effect::<Effect>("effect");
+        });
+        app.world_mut().run_schedule(PreStartup);
+        app.world_mut().flush();
+        app
+    }
+
+    #[test]
+    fn valid_modifiers() {
+        let app = prepare_app();
+        let context = ValidationContext::new(app.world());
+
+        for str in [
+            "plain text",
+            "`effect`[effect]",
+            "`effect with args`[effect(1)]",
+            "`effect with named args`[effect(intensity=1)]",
+            "`nested `effect`[effect]`[!red, !blue]",
+        ] {
+            let spans = PrettyTextParser::spans(str).unwrap();
+            assert_eq!(context.validate(&spans), Ok(()), "{str}");
+        }
+    }
+
+    #[test]
+    fn invalid_modifiers() {
+        let app = prepare_app();
+
+        let errors = PrettyTextParser::validate(
+            "`typo`[efect] and `nested `args`[effect(1, 2), effect(intensty=1)]`[!rde] \
+             `twice`[effect(1, intensity=2)]",
+            app.world(),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            errors.0,
+            vec![
+                ValidationError::UnregisteredEffect("efect".into()),
+                ValidationError::UnregisteredStyle("rde".into()),
+                ValidationError::TooManyArguments {
+                    tag: "effect".into(),
+                    expected: 1,
+                    found: 2,
+                },
+                ValidationError::UnknownField {
+                    tag: "effect".into(),
+                    field: "intensty".into(),
+                },
+                ValidationError::DuplicateField {
+                    tag: "effect".into(),
+                    field: "intensity".into(),
+                },
+            ]
+        );
+
+        // The parser rejects repeated named arguments, but constructed spans may not.
+        let mut spans = PrettyTextParser::spans("`twice`[effect(intensity=1)]").unwrap();
+        let TextSpanBundle::Span { mods, .. } = &mut spans.0[0] else {
+            panic!("expected span");
+        };
+        let Modifier::Effect(effect) = &mut mods.0[0] else {
+            panic!("expected effect");
+        };
+        effect.named_args.push(NamedArg::new("intensity", "2"));
+        assert_eq!(
+            ValidationContext::new(app.world()).validate(&spans),
+            Err(ValidationErrors(vec![ValidationError::DuplicateField {
+                tag: "effect".into(),
+                field: "intensity".into(),
+            }]))
+        );
+
+        let errors = PrettyTextParser::validate("`unclosed", app.world()).unwrap_err();
+        assert!(matches!(
+            errors.0.as_slice(),
+            [ValidationError::Parse(err)] if err.kind() == ParseErrorKind::UnclosedSpan
+        ));
+    }
+}
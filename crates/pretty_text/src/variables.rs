@@ -0,0 +1,559 @@
+//! Interpolates runtime values into text with the `{$name}` syntax.
+//!
+//! Variables are resolved from the [`PrettyTextVariables`] resource when a
+//! span is spawned. The resolved value is inserted directly into a
+//! [`TextSpan`], so it is never parsed as markup.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::variables::*;
+#![doc = include_str!("../docs/pretty.txt")]
+//! #
+//! # let mut world = World::new();
+//! # world.init_resource::<PrettyTextVariables>();
+//! // Set a variable.
+//! world
+//!     .resource_mut::<PrettyTextVariables>()
+//!     .set("player_name", "`Ferris`");
+//!
+//! // Displays "Hello, `Ferris`!" with a wave.
+//! world.spawn(pretty!("Hello, `{$player_name}`[wave]!"));
+//! ```
+//!
+//! Variables can also be bound to a field of a [`Component`] with a
+//! [reflect path](bevy::reflect::ParsedPath).
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::variables::*;
+#![doc = include_str!("../docs/pretty.txt")]
+//! #
+//! #[derive(Component, Reflect)]
+//! struct Wallet {
+//!     gold: u32,
+//! }
+//!
+//! # fn doc() -> Result {
+//! # let mut world = World::new();
+//! # world.init_resource::<PrettyTextVariables>();
+//! let player = world.spawn(Wallet { gold: 10 }).id();
+//! world
+//!     .resource_mut::<PrettyTextVariables>()
+//!     .bind::<Wallet>("gold", player, ".gold")?;
+//!
+//! // `LiveVariables` keeps the text in sync with the wallet.
+//! world.spawn((LiveVariables, pretty!("You have {$gold} gold")));
+//! # Ok(())
+//! # }
+//! # doc().unwrap();
+//! ```
+//!
+//! A variable that is not found is displayed as its placeholder, e.g. `{$gold}`.
+//!
+//! # Conditions
+//!
+//! Variables can also select between segments of text. `{?name|then|otherwise}`
+//! spawns `then` if `name` is truthy, and `otherwise` if it is not found, or is
+//! `""`, `"0"` or `"false"`. `otherwise` may be omitted.
+//!
+//! `{#name|one|other}` and `{#name|zero|one|other}` select the plural form of
+//! a number variable. `zero` is selected by exactly `0`, and `one` by the
+//! [`PluralRule`] of [`PrettyTextVariables`], which follows English by
+//! default. A variable that is not a number selects `other`.
+//!
+//! Only two forms are supported, so languages with more plural categories,
+//! e.g. Russian or Arabic, can not be expressed. Set the plural rule of other
+//! languages with [`PrettyTextVariables::set_plural_rule`] when the locale
+//! changes:
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::variables::*;
+//! # let mut world = World::new();
+//! # world.init_resource::<PrettyTextVariables>();
+//! // French uses the singular for 0 and 1.5.
+//! world
+//!     .resource_mut::<PrettyTextVariables>()
+//!     .set_plural_rule(PluralRule::FRENCH);
+//! ```
+//!
+//! Branches may contain any markup, including other conditions. Within a
+//! branch, `|` is escaped with `\|`.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use pretty_text::variables::*;
+#![doc = include_str!("../docs/pretty.txt")]
+//! #
+//! # let mut world = World::new();
+//! # world.init_resource::<PrettyTextVariables>();
+//! let mut variables = world.resource_mut::<PrettyTextVariables>();
+//! variables.set("has_key", true);
+//! variables.set("apples", 3);
+//!
+//! // Displays "You unlock it. You have 3 apples."
+//! world.spawn(pretty!(
+//!     "{?has_key|You `unlock`[shake] it.|It's locked.} \
+//!     You have {#apples|no apples|an apple|{$apples} apples}."
+//! ));
+//! ```
+//!
+//! Conditions are evaluated once, when spawned with
+//! [`PrettyTextSpans`](crate::parser::PrettyTextSpans).
+
+use std::borrow::Cow;
+
+use bevy::ecs::component::Tick;
+use bevy::ecs::system::SystemChangeTick;
+use bevy::ecs::world::DeferredWorld;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::reflect::{ParsedPath, ReflectPath};
+
+use crate::parser::{Span, TextSpanBundle};
+
+/// Resolves [`TextVariable`] spans and updates [`LiveVariables`].
+#[derive(Debug)]
+pub struct VariablesPlugin;
+
+impl Plugin for VariablesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PrettyTextVariables>()
+            .add_systems(
+                PostUpdate,
+                update_live_variables
+                    .run_if(resource_changed::<PrettyTextVariables>.or(bindings_changed))
+                    .before(bevy::text::Update2dText),
+            )
+            .add_observer(resolve_variable);
+
+        app.register_type::<TextVariable>()
+            .register_type::<LiveVariables>();
+    }
+}
+
+/// Stores the values of variables interpolated into text.
+///
+/// See [`variables`](crate::variables).
+#[derive(Debug, Default, Resource)]
+pub struct PrettyTextVariables {
+    variables: HashMap<Cow<'static, str>, Variable>,
+    plural_rule: PluralRule,
+}
+
+/// Selects the plural form of a number, see [`variables`](crate::variables#conditions).
+///
+/// The default rule is [`PluralRule::ENGLISH`].
+#[derive(Debug, Clone, Copy)]
+pub struct PluralRule(pub fn(f64) -> PluralForm);
+
+impl PluralRule {
+    /// `one` for exactly `1`, e.g. English and German.
+    pub const ENGLISH: Self = Self(|count| {
+        if count == 1. {
+            PluralForm::One
+        } else {
+            PluralForm::Other
+        }
+    });
+
+    /// `one` from `0` up to, but excluding, `2`, e.g. French and Portuguese.
+    pub const FRENCH: Self = Self(|count| {
+        if (0. ..2.).contains(&count) {
+            PluralForm::One
+        } else {
+            PluralForm::Other
+        }
+    });
+
+    /// Always `other`, for languages without plurals, e.g. Japanese and Chinese.
+    pub const INVARIANT: Self = Self(|_| PluralForm::Other);
+}
+
+impl Default for PluralRule {
+    fn default() -> Self {
+        Self::ENGLISH
+    }
+}
+
+/// The plural form selected by a [`PluralRule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluralForm {
+    /// The singular form, e.g. `apple`.
+    One,
+    /// The plural form, e.g. `apples`.
+    Other,
+}
+
+enum Variable {
+    Value(String),
+    Binding {
+        resolve: Box<dyn Fn(&World) -> Option<String> + Send + Sync>,
+        // Whether the bound component changed since the last run.
+        changed: Box<dyn Fn(&World, Tick, Tick) -> bool + Send + Sync>,
+    },
+}
+
+impl std::fmt::Debug for Variable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Value(value) => f.debug_tuple("Value").field(value).finish(),
+            Self::Binding { .. } => f.debug_struct("Binding").finish_non_exhaustive(),
+        }
+    }
+}
+
+impl PrettyTextVariables {
+    /// Set the value of a variable.
+    pub fn set(&mut self, name: impl Into<Cow<'static, str>>, value: impl ToString) {
+        self.variables
+            .insert(name.into(), Variable::Value(value.to_string()));
+    }
+
+    /// Bind a variable to a field of the component `C` on `entity`.
+    ///
+    /// `path` is a [reflect path](bevy::reflect::ParsedPath) into `C`. Strings are
+    /// displayed as is, and any other value with its [`Debug`] representation.
+    ///
+    /// Returns an error if `path` is invalid.
+    pub fn bind<C: Component + Reflect>(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        entity: Entity,
+        path: &str,
+    ) -> Result {
+        let path = ParsedPath::parse(path)
+            .map_err(|err| BevyError::from(format!("invalid reflect path `{path}`: {err}")))?;
+
+        self.variables.insert(
+            name.into(),
+            Variable::Binding {
+                resolve: Box::new(move |world| {
+                    let component = world.get::<C>(entity)?;
+                    let value = (&path)
+                        .reflect_element(component.as_partial_reflect())
+                        .ok()?;
+
+                    Some(
+                        value
+                            .try_downcast_ref::<String>()
+                            .cloned()
+                            .unwrap_or_else(|| format!("{value:?}")),
+                    )
+                }),
+                changed: Box::new(move |world, last_run, this_run| {
+                    world
+                        .get_entity(entity)
+                        .ok()
+                        .and_then(|entity| entity.get_ref::<C>())
+                        .is_some_and(|component| {
+                            component.last_changed().is_newer_than(last_run, this_run)
+                        })
+                }),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Remove a variable.
+    pub fn remove(&mut self, name: &str) {
+        self.variables.remove(name);
+    }
+
+    /// Set the rule that selects the plural form of `{#name|one|other}`.
+    ///
+    /// Spawned text is not updated.
+    pub fn set_plural_rule(&mut self, rule: PluralRule) {
+        self.plural_rule = rule;
+    }
+
+    /// Returns the rule that selects the plural form of `{#name|one|other}`.
+    pub fn plural_rule(&self) -> PluralRule {
+        self.plural_rule
+    }
+
+    /// Returns the current value of a variable.
+    ///
+    /// Returns `None` if the variable is not found, or if it is bound to a
+    /// component that does not exist.
+    pub fn resolve(&self, name: &str, world: &World) -> Option<String> {
+        match self.variables.get(name)? {
+            Variable::Value(value) => Some(value.clone()),
+            Variable::Binding { resolve, .. } => resolve(world),
+        }
+    }
+}
+
+/// A [`TextSpan`] that displays the value of a variable.
+///
+/// Produced by the `{$name}` syntax, see [`variables`](crate::variables).
+#[derive(Debug, Clone, PartialEq, Eq, Component, Reflect)]
+#[require(TextSpan)]
+pub struct TextVariable(pub Cow<'static, str>);
+
+/// Keeps the [`TextVariable`] spans of a text hierarchy in sync with their
+/// values.
+///
+/// Spans are updated when [`PrettyTextVariables`] changes, or when a component
+/// bound with [`PrettyTextVariables::bind`] changes. Changed spans are laid out
+/// again without resetting their effects.
+///
+/// Without `LiveVariables`, variables are only resolved when spawned.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct LiveVariables;
+
+fn resolve(name: &str, world: &World) -> Option<String> {
+    world
+        .get_resource::<PrettyTextVariables>()
+        .and_then(|variables| variables.resolve(name, world))
+}
+
+fn placeholder(name: &str) -> String {
+    format!("{{${name}}}")
+}
+
+// Replaces conditions with their selected branch.
+pub(crate) fn select_branches(bundles: Vec<TextSpanBundle>, world: &World) -> Vec<TextSpanBundle> {
+    let mut selected = Vec::with_capacity(bundles.len());
+    for bundle in bundles.into_iter() {
+        match bundle {
+            TextSpanBundle::Span {
+                span: Span::Bundles(bundles),
+                mods,
+                source,
+            } => selected.push(TextSpanBundle::Span {
+                span: Span::Bundles(select_branches(bundles, world)),
+                mods,
+                source,
+            }),
+            TextSpanBundle::Conditional {
+                variable,
+                then,
+                otherwise,
+            } => {
+                let value = resolve(&variable, world);
+                let branch = if is_truthy(value.as_deref()) {
+                    then
+                } else {
+                    otherwise
+                };
+                selected.extend(select_branches(branch, world));
+            }
+            TextSpanBundle::Plural {
+                variable,
+                mut branches,
+            } => {
+                let value = resolve(&variable, world);
+                let count = value
+                    .as_deref()
+                    .and_then(|value| value.trim().parse::<f64>().ok());
+                if count.is_none() {
+                    warn!("plural variable `{variable}` is not a number: {value:?}");
+                }
+
+                let rule = world
+                    .get_resource::<PrettyTextVariables>()
+                    .map(PrettyTextVariables::plural_rule)
+                    .unwrap_or_default();
+                let other = branches.len() - 1;
+                let index = match count {
+                    Some(0.0) if branches.len() == 3 => 0,
+                    Some(count) if rule.0(count) == PluralForm::One => other - 1,
+                    _ => other,
+                };
+                selected.extend(select_branches(branches.swap_remove(index), world));
+            }
+            bundle => selected.push(bundle),
+        }
+    }
+
+    selected
+}
+
+fn is_truthy(value: Option<&str>) -> bool {
+    value.is_some_and(|value| !matches!(value.trim(), "" | "0" | "false"))
+}
+
+fn resolve_variable(trigger: Trigger<OnAdd, TextVariable>, mut world: DeferredWorld) {
+    let entity = trigger.target();
+    let name = &world.get::<TextVariable>(entity).unwrap().0;
+    let value = resolve(name, &world).unwrap_or_else(|| {
+        warn!("variable `{name}` not found");
+        placeholder(name)
+    });
+    world.get_mut::<TextSpan>(entity).unwrap().0 = value;
+}
+
+fn bindings_changed(
+    world: &World,
+    variables: Res<PrettyTextVariables>,
+    ticks: SystemChangeTick,
+) -> bool {
+    variables.variables.values().any(|variable| match variable {
+        Variable::Binding { changed, .. } => changed(world, ticks.last_run(), ticks.this_run()),
+        Variable::Value(_) => false,
+    })
+}
+
+// Bindings may read any component, so values are resolved from the `World`
+// and written with commands.
+fn update_live_variables(
+    mut commands: Commands,
+    spans: Query<(Entity, &TextVariable, &TextSpan, &ChildOf)>,
+    live: Query<(), With<LiveVariables>>,
+    world: &World,
+) {
+    for (entity, variable, span, child_of) in spans.iter() {
+        if !live.contains(child_of.parent()) {
+            continue;
+        }
+
+        let value = resolve(&variable.0, world).unwrap_or_else(|| placeholder(&variable.0));
+        if value != span.0 {
+            commands.entity(entity).insert(TextSpan(value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::dynamic_effects::{DynamicEffect, PrettyTextEffectAppExt};
+    use crate::parser::PrettyTextParser;
+    use crate::test::prepare_app;
+
+    use super::{LiveVariables, PluralRule, PrettyTextVariables, TextVariable};
+
+    #[derive(Component, Reflect)]
+    struct Wallet {
+        gold: u32,
+    }
+
+    fn spans(app: &mut App) -> Vec<String> {
+        let mut spans = app
+            .world_mut()
+            .query_filtered::<&TextSpan, With<TextVariable>>();
+        spans.iter(app.world()).map(|span| span.0.clone()).collect()
+    }
+
+    #[test]
+    fn resolve_variables() {
+        let mut app = prepare_app();
+        let mut variables = app.world_mut().resource_mut::<PrettyTextVariables>();
+        variables.set("name", "`Ferris`[wave]");
+
+        app.world_mut()
+            .spawn(PrettyTextParser::bundle("Hello, `{$name}`[!red]! {$missing}").unwrap());
+        app.world_mut().flush();
+
+        let mut spans = spans(&mut app);
+        spans.sort();
+        assert_eq!(spans, vec!["`Ferris`[wave]", "{$missing}"]);
+    }
+
+    #[test]
+    fn conditions() {
+        let mut app = prepare_app();
+        let mut variables = app.world_mut().resource_mut::<PrettyTextVariables>();
+        variables.set("has_key", true);
+        variables.set("apples", 1);
+
+        let markup = "{?has_key|`unlocked`[wave]|locked}{?missing|a|b}{?missing|c} \
+            {#apples|no apples|an apple|{$apples} apples}";
+        let text = |app: &mut App| {
+            let entity = app
+                .world_mut()
+                .spawn(PrettyTextParser::bundle(markup).unwrap())
+                .id();
+            app.world_mut().flush();
+
+            let children = app.world().get::<Children>(entity).unwrap();
+            children
+                .iter()
+                .filter_map(|child| app.world().get::<TextSpan>(child))
+                .map(|span| span.0.as_str())
+                .collect::<String>()
+        };
+        assert_eq!(text(&mut app), "unlockedb an apple");
+
+        let mut variables = app.world_mut().resource_mut::<PrettyTextVariables>();
+        variables.set("has_key", "false");
+        variables.set("apples", 0);
+        assert_eq!(text(&mut app), "lockedb no apples");
+
+        app.world_mut()
+            .resource_mut::<PrettyTextVariables>()
+            .set("apples", 2);
+        assert_eq!(text(&mut app), "lockedb 2 apples");
+
+        let mut variables = app.world_mut().resource_mut::<PrettyTextVariables>();
+        variables.set_plural_rule(PluralRule::FRENCH);
+        variables.set("apples", 1.5);
+        assert_eq!(text(&mut app), "lockedb an apple");
+
+        let mut variables = app.world_mut().resource_mut::<PrettyTextVariables>();
+        variables.set_plural_rule(PluralRule::INVARIANT);
+        variables.set("apples", 1);
+        assert_eq!(text(&mut app), "lockedb 1 apples");
+    }
+
+    #[derive(Default, Component)]
+    struct Effect;
+
+    impl DynamicEffect for Effect {
+        fn insert_from_args(
+            &self,
+            _: &[std::borrow::Cow<'static, str>],
+            entity: &mut EntityCommands,
+        ) -> Result<()> {
+            entity.insert(Effect);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn live_variables() {
+        let mut app = prepare_app();
+        app.register_pretty_effect::<Effect>("effect");
+        app.world_mut().run_schedule(PreStartup);
+
+        let wallet = app.world_mut().spawn(Wallet { gold: 10 }).id();
+        app.world_mut()
+            .resource_mut::<PrettyTextVariables>()
+            .bind::<Wallet>("gold", wallet, ".gold")
+            .unwrap();
+
+        app.world_mut().spawn((
+            LiveVariables,
+            PrettyTextParser::bundle("`{$gold}`[effect]").unwrap(),
+        ));
+        app.world_mut().flush();
+        app.update();
+        assert_eq!(spans(&mut app), vec!["10"]);
+
+        app.world_mut().get_mut::<Wallet>(wallet).unwrap().gold = 25;
+        app.update();
+        assert_eq!(spans(&mut app), vec!["25"]);
+
+        app.world_mut()
+            .resource_mut::<PrettyTextVariables>()
+            .set("gold", "lots");
+        app.update();
+        assert_eq!(spans(&mut app), vec!["lots"]);
+
+        // Spans are updated in place, so effects are not reset.
+        let mut effects = app
+            .world_mut()
+            .query_filtered::<(), (With<TextVariable>, With<Effect>)>();
+        assert_eq!(effects.iter(app.world()).count(), 1);
+
+        assert!(
+            app.world_mut()
+                .resource_mut::<PrettyTextVariables>()
+                .bind::<Wallet>("gold", wallet, ".[")
+                .is_err()
+        );
+    }
+}